@@ -0,0 +1,282 @@
+//animation.rs
+use crate::camera::Camera;
+use crate::hittable::Hittable;
+use crate::vec3::{Point3, Vec3};
+use std::fs;
+use std::io;
+use std::ops::{Add, Mul, RangeInclusive, Sub};
+use std::path::{Path, PathBuf};
+
+/// 关键帧之间的插值方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// 线性插值
+    #[default]
+    Linear,
+    /// Catmull-Rom 样条，经过所有关键帧且速度连续
+    CatmullRom,
+    /// 三次 Bezier：lookfrom、lookat 使用关键帧上设置的控制柄，
+    /// 未设置控制柄的量取 Catmull-Rom 切线的 1/3（与 CatmullRom 相同）
+    Bezier,
+}
+
+/// 相机关键帧
+#[derive(Debug, Clone, Copy)]
+pub struct CameraKeyframe {
+    pub frame: f64,
+    pub lookfrom: Point3,
+    pub lookat: Point3,
+    pub vup: Vec3,
+    pub vfov: f64,
+    pub focus_dist: f64,
+    pub lookfrom_handles: Option<(Vec3, Vec3)>, // Bezier 进入、离开该关键帧的控制柄，相对 lookfrom 的偏移
+    pub lookat_handles: Option<(Vec3, Vec3)>,   // 同上，相对 lookat 的偏移
+}
+
+impl CameraKeyframe {
+    /// 以相机当前的视角参数创建关键帧
+    pub fn from_camera(frame: f64, cam: &Camera) -> Self {
+        Self {
+            frame,
+            lookfrom: cam.lookfrom,
+            lookat: cam.lookat,
+            vup: cam.vup,
+            vfov: cam.vfov,
+            focus_dist: cam.focus_dist,
+            lookfrom_handles: None,
+            lookat_handles: None,
+        }
+    }
+
+    /// lookfrom 的 Bezier 控制柄：in_handle 控制进入该关键帧的方向，out_handle 控制离开的方向
+    pub fn with_lookfrom_handles(mut self, in_handle: Vec3, out_handle: Vec3) -> Self {
+        self.lookfrom_handles = Some((in_handle, out_handle));
+        self
+    }
+
+    /// lookat 的 Bezier 控制柄
+    pub fn with_lookat_handles(mut self, in_handle: Vec3, out_handle: Vec3) -> Self {
+        self.lookat_handles = Some((in_handle, out_handle));
+        self
+    }
+
+    /// 将关键帧参数写回相机
+    pub fn apply(&self, cam: &mut Camera) {
+        cam.lookfrom = self.lookfrom;
+        cam.lookat = self.lookat;
+        cam.vup = self.vup;
+        cam.vfov = self.vfov;
+        cam.focus_dist = self.focus_dist;
+    }
+}
+
+/// 相机关键帧动画
+#[derive(Debug, Clone, Default)]
+pub struct CameraAnimation {
+    keyframes: Vec<CameraKeyframe>,
+    pub interpolation: Interpolation,
+}
+
+impl CameraAnimation {
+    pub fn new(interpolation: Interpolation) -> Self {
+        Self {
+            keyframes: Vec::new(),
+            interpolation,
+        }
+    }
+
+    // 添加关键帧，按帧号保持有序；同一帧号会覆盖旧的关键帧
+    pub fn add_keyframe(&mut self, key: CameraKeyframe) {
+        match self
+            .keyframes
+            .binary_search_by(|k| k.frame.total_cmp(&key.frame))
+        {
+            Ok(idx) => self.keyframes[idx] = key,
+            Err(idx) => self.keyframes.insert(idx, key),
+        }
+    }
+
+    pub fn keyframes(&self) -> &[CameraKeyframe] {
+        &self.keyframes
+    }
+
+    /// 第一个到最后一个关键帧覆盖的帧范围
+    pub fn frame_range(&self) -> Option<RangeInclusive<i32>> {
+        let first = self.keyframes.first()?;
+        let last = self.keyframes.last()?;
+        Some(first.frame.floor() as i32..=last.frame.ceil() as i32)
+    }
+
+    /// 计算任意（可为小数）帧号处的相机参数，范围外保持首尾关键帧
+    pub fn evaluate(&self, frame: f64) -> Option<CameraKeyframe> {
+        let keys = &self.keyframes;
+        let first = keys.first()?;
+        let last = keys.last()?;
+        if frame <= first.frame {
+            return Some(CameraKeyframe { frame, ..*first });
+        }
+        if frame >= last.frame {
+            return Some(CameraKeyframe { frame, ..*last });
+        }
+
+        // 找到 frame 所在的区间 [k1, k2]
+        let i = keys.partition_point(|k| k.frame <= frame) - 1;
+        let k1 = &keys[i];
+        let k2 = &keys[i + 1];
+        let k0 = if i > 0 { &keys[i - 1] } else { k1 };
+        let k3 = keys.get(i + 2).unwrap_or(k2);
+        let s = (frame - k1.frame) / (k2.frame - k1.frame);
+
+        let vup = self.interpolate(k0, k1, k2, k3, s, |k| k.vup);
+        Some(CameraKeyframe {
+            frame,
+            lookfrom: self
+                .interpolate_handles(k0, k1, k2, k3, s, |k| (k.lookfrom, k.lookfrom_handles)),
+            lookat: self.interpolate_handles(k0, k1, k2, k3, s, |k| (k.lookat, k.lookat_handles)),
+            vup: if vup.near_zero() {
+                k1.vup
+            } else {
+                vup.unit_vector()
+            },
+            vfov: self.interpolate(k0, k1, k2, k3, s, |k| k.vfov),
+            focus_dist: self.interpolate(k0, k1, k2, k3, s, |k| k.focus_dist),
+            lookfrom_handles: None,
+            lookat_handles: None,
+        })
+    }
+
+    /// 将指定帧的参数写回相机，没有关键帧时相机保持不变
+    pub fn apply(&self, cam: &mut Camera, frame: f64) {
+        if let Some(key) = self.evaluate(frame) {
+            key.apply(cam);
+        }
+    }
+
+    fn interpolate<T>(
+        &self,
+        k0: &CameraKeyframe,
+        k1: &CameraKeyframe,
+        k2: &CameraKeyframe,
+        k3: &CameraKeyframe,
+        s: f64,
+        get: impl Fn(&CameraKeyframe) -> T,
+    ) -> T
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f64, Output = T>,
+    {
+        let (p1, p2) = (get(k1), get(k2));
+        match self.interpolation {
+            Interpolation::Linear => p1 + (p2 - p1) * s,
+            Interpolation::CatmullRom | Interpolation::Bezier => {
+                let (m1, m2) = catmull_rom_tangents(k0, k1, k2, k3, &get);
+                hermite(p1, p2, m1, m2, s)
+            }
+        }
+    }
+
+    // 带控制柄的量：Bezier 模式下优先使用关键帧上的控制柄
+    fn interpolate_handles(
+        &self,
+        k0: &CameraKeyframe,
+        k1: &CameraKeyframe,
+        k2: &CameraKeyframe,
+        k3: &CameraKeyframe,
+        s: f64,
+        get: impl Fn(&CameraKeyframe) -> (Vec3, Option<(Vec3, Vec3)>),
+    ) -> Vec3 {
+        let (p1, handles1) = get(k1);
+        let (p2, handles2) = get(k2);
+        if self.interpolation != Interpolation::Bezier {
+            return self.interpolate(k0, k1, k2, k3, s, |k| get(k).0);
+        }
+        // 三次 Hermite 与控制点为 p1 + m1/3、p2 - m2/3 的 Bezier 等价
+        let (m1, m2) = catmull_rom_tangents(k0, k1, k2, k3, &|k: &CameraKeyframe| get(k).0);
+        let c1 = p1 + handles1.map_or(m1 / 3.0, |(_, out_handle)| out_handle);
+        let c2 = p2 + handles2.map_or(-m2 / 3.0, |(in_handle, _)| in_handle);
+        bezier(p1, c1, c2, p2, s)
+    }
+
+    /// 渲染帧序列，输出为 `{prefix}_{frame:04}.ppm`；skip_existing 为 true 时跳过已存在的帧
+    pub fn render_sequence(
+        &self,
+        cam: &Camera,
+        world: &dyn Hittable,
+        frames: RangeInclusive<i32>,
+        prefix: &str,
+        skip_existing: bool,
+    ) -> io::Result<Vec<PathBuf>> {
+        if let Some(dir) = Path::new(prefix).parent()
+            && !dir.as_os_str().is_empty()
+        {
+            fs::create_dir_all(dir)?;
+        }
+
+        let total = frames.clone().count();
+        let mut written = Vec::with_capacity(total);
+        for frame in frames {
+            let path = frame_path(prefix, frame);
+            if skip_existing && path.exists() {
+                eprintln!("[SEQUENCE] Skipping existing frame {}", path.display());
+                written.push(path);
+                continue;
+            }
+            eprintln!("[SEQUENCE] Rendering frame {} ({} total)", frame, total);
+            let mut frame_cam = cam.clone();
+            self.apply(&mut frame_cam, frame as f64);
+            frame_cam.render_to_file(world, &path)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+/// 帧序列中某一帧的文件路径
+pub fn frame_path(prefix: &str, frame: i32) -> PathBuf {
+    PathBuf::from(format!("{}_{:04}.ppm", prefix, frame))
+}
+
+// 区间 [k1, k2] 两端的 Catmull-Rom 切线（以参数 s ∈ [0, 1] 计），
+// 非均匀帧间隔下按当前区间长度缩放
+fn catmull_rom_tangents<T>(
+    k0: &CameraKeyframe,
+    k1: &CameraKeyframe,
+    k2: &CameraKeyframe,
+    k3: &CameraKeyframe,
+    get: &impl Fn(&CameraKeyframe) -> T,
+) -> (T, T)
+where
+    T: Copy + Sub<Output = T> + Mul<f64, Output = T>,
+{
+    let (p1, p2) = (get(k1), get(k2));
+    let span = k2.frame - k1.frame;
+    let m1 = if k0.frame < k1.frame {
+        (p2 - get(k0)) * (span / (k2.frame - k0.frame))
+    } else {
+        p2 - p1
+    };
+    let m2 = if k3.frame > k2.frame {
+        (get(k3) - p1) * (span / (k3.frame - k1.frame))
+    } else {
+        p2 - p1
+    };
+    (m1, m2)
+}
+
+// 三次 Bezier（de Casteljau 展开式）
+fn bezier(p0: Vec3, c1: Vec3, c2: Vec3, p3: Vec3, s: f64) -> Vec3 {
+    let t = 1.0 - s;
+    t * t * t * p0 + 3.0 * t * t * s * c1 + 3.0 * t * s * s * c2 + s * s * s * p3
+}
+
+// 三次 Hermite 插值
+fn hermite<T>(p1: T, p2: T, m1: T, m2: T, s: f64) -> T
+where
+    T: Copy + Add<Output = T> + Mul<f64, Output = T>,
+{
+    let s2 = s * s;
+    let s3 = s2 * s;
+    p1 * (2.0 * s3 - 3.0 * s2 + 1.0)
+        + m1 * (s3 - 2.0 * s2 + s)
+        + p2 * (-2.0 * s3 + 3.0 * s2)
+        + m2 * (s3 - s2)
+}
@@ -0,0 +1,152 @@
+//atmosphere.rs
+// 充满整个场景的全局介质（雾、霾、大气），没有边界，相机光线从介质内部出发。
+// 密度随高度指数衰减：ρ(p) = density · exp(-(h(p) - base_height) / scale_height)，h 为沿 up 的高度，
+// 沿直线的光学厚度有解析式，可以直接反演采样自由程，不需要 delta tracking。
+// 各通道消光不同时按通道做单样本 MIS：随机选一个通道按其消光采样距离，权重除以各通道 pdf 的平均
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::phase::{HenyeyGreenstein, PhaseFunction};
+use crate::ray::Ray;
+use crate::rtweekend::{random_double, INFINITY};
+use crate::vec3::{self, Point3, Vec3};
+
+pub struct Atmosphere {
+    pub density: f64,      // base_height 处的密度
+    pub sigma_a: Color,    // 单位密度下的吸收系数
+    pub sigma_s: Color,    // 单位密度下的散射系数
+    pub up: Vec3,          // 高度方向（单位向量）
+    pub base_height: f64,  // 密度等于 density 的高度
+    pub scale_height: f64, // 密度衰减为 1/e 的高度差，INFINITY 为均匀介质
+    pub phase: Arc<dyn PhaseFunction>,
+}
+
+/// 沿光线采样的结果
+pub enum AtmosphereEvent {
+    /// 在介质中散射，scattered 为新的光线
+    Scatter { scattered: Ray, weight: Color },
+    /// 光线穿过介质到达表面（或无穷远），之后的着色乘以 weight
+    Pass { weight: Color },
+}
+
+impl Atmosphere {
+    /// 各向同性散射的均匀介质
+    pub fn new(density: f64, sigma_a: Color, sigma_s: Color) -> Self {
+        Self {
+            density,
+            sigma_a,
+            sigma_s,
+            up: Vec3::new(0.0, 1.0, 0.0),
+            base_height: 0.0,
+            scale_height: INFINITY,
+            phase: Arc::new(HenyeyGreenstein::new(0.0)),
+        }
+    }
+
+    /// 密度沿 up 方向按指数衰减
+    pub fn with_height_falloff(mut self, up: Vec3, base_height: f64, scale_height: f64) -> Self {
+        self.up = vec3::unit_vector(up);
+        self.base_height = base_height;
+        self.scale_height = scale_height;
+        self
+    }
+
+    /// 使用各向异性相函数（霾、雾中的 Mie 散射通常强烈前向）
+    pub fn with_phase(mut self, phase: Arc<dyn PhaseFunction>) -> Self {
+        self.phase = phase;
+        self
+    }
+
+    /// p 处的密度
+    pub fn density_at(&self, p: &Point3) -> f64 {
+        let h = vec3::dot(*p, self.up) - self.base_height;
+        // 限制指数避免溢出，远低于基准高度时密度已经足够大
+        self.density * (-h / self.scale_height).min(50.0).exp()
+    }
+
+    // 光线起点处的 ρ·|dir|，以及密度沿参数 t 的指数衰减率 k：ρ(t)·|dir| = a·e^{-kt}
+    fn falloff(&self, r: &Ray) -> (f64, f64) {
+        let a = self.density_at(&r.origin()) * r.direction().length();
+        let k = vec3::dot(r.direction(), self.up) / self.scale_height;
+        (a, k)
+    }
+
+    /// 沿光线从 t = 0 到 t 的密度积分 D(t) = ∫ρ ds，光学厚度为 σt · D(t)
+    pub fn column_density(&self, r: &Ray, t: f64) -> f64 {
+        let (a, k) = self.falloff(r);
+        if a <= 0.0 {
+            return 0.0;
+        }
+        if k.abs() < 1e-12 {
+            return a * t;
+        }
+        if t.is_infinite() {
+            // 向上的光线积分收敛，水平或向下的光线发散
+            return if k > 0.0 { a / k } else { INFINITY };
+        }
+        -a * (-k * t).exp_m1() / k
+    }
+
+    // D(t) = d 的解，到无穷远也达不到 d 时为 INFINITY
+    fn invert_column_density(&self, r: &Ray, d: f64) -> f64 {
+        let (a, k) = self.falloff(r);
+        if a <= 0.0 {
+            return INFINITY;
+        }
+        if k.abs() < 1e-12 {
+            return d / a;
+        }
+        let x = d * k / a;
+        if x >= 1.0 {
+            return INFINITY;
+        }
+        -(-x).ln_1p() / k
+    }
+
+    /// 光线从 t = 0 到 t_max 的透射率（阴影光线用）
+    pub fn transmittance(&self, r: &Ray, t_max: f64) -> Color {
+        let sigma_t = self.sigma_a + self.sigma_s;
+        let d = self.column_density(r, t_max);
+        Color::new(
+            (-sigma_t.x * d).exp(),
+            (-sigma_t.y * d).exp(),
+            (-sigma_t.z * d).exp(),
+        )
+    }
+
+    /// 在 (0, t_max) 内采样一次自由程：散射或者穿过
+    pub fn sample(&self, r: &Ray, t_max: f64) -> AtmosphereEvent {
+        let sigma_t = self.sigma_a + self.sigma_s;
+        let channel = ((random_double() * 3.0) as usize).min(2);
+        let t = if sigma_t[channel] > 0.0 {
+            let d = -random_double().ln() / sigma_t[channel];
+            self.invert_column_density(r, d)
+        } else {
+            INFINITY
+        };
+        let mean = |c: Color| (c.x + c.y + c.z) / 3.0;
+
+        if t < t_max {
+            // pdf 与散射系数中的密度相互抵消：权重 = σs·T / mean(σt·T)
+            let tr = self.transmittance(r, t);
+            let pdf = mean(sigma_t * tr);
+            let weight = if pdf > 0.0 {
+                self.sigma_s * tr / pdf
+            } else {
+                Color::default()
+            };
+            let scattered = Ray::new(r.at(t), self.phase.sample(r.direction()), r.time())
+                .with_wavelength(r.wavelength());
+            return AtmosphereEvent::Scatter { scattered, weight };
+        }
+
+        let tr = self.transmittance(r, t_max);
+        let p_pass = mean(tr);
+        let weight = if p_pass > 0.0 {
+            tr / p_pass
+        } else {
+            Color::default()
+        };
+        AtmosphereEvent::Pass { weight }
+    }
+}
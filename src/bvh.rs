@@ -1,5 +1,6 @@
 // bvh.rs
 use crate::aabb::Aabb;
+use crate::color::Color;
 use crate::hittable::{HitRecord, Hittable};
 use crate::interval::Interval;
 use crate::ray::Ray;
@@ -12,6 +13,7 @@ pub struct BvhNode {
     left: Arc<dyn Hittable + Send + Sync>,
     right: Arc<dyn Hittable + Send + Sync>,
     bbox: Aabb,
+    media: bool, // 子树中含有彩色介质
 }
 
 impl BvhNode {
@@ -57,7 +59,13 @@ impl BvhNode {
         };
 
         let bbox = Aabb::new_boxes(&left.bounding_box(), &right.bounding_box());
-        Self { left, right, bbox }
+        let media = left.has_chromatic_media() || right.has_chromatic_media();
+        Self {
+            left,
+            right,
+            bbox,
+            media,
+        }
     }
 }
 
@@ -100,4 +108,20 @@ impl Hittable for BvhNode {
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
+
+    fn transmittance(&self, r: &Ray, ray_t: &Interval) -> Color {
+        if !self.media || !self.bbox.hit(r, *ray_t) {
+            return Color::new(1.0, 1.0, 1.0);
+        }
+        // 只有一个物体时左右子节点是同一个，只能计一次
+        let left = self.left.transmittance(r, ray_t);
+        if Arc::ptr_eq(&self.left, &self.right) {
+            return left;
+        }
+        left * self.right.transmittance(r, ray_t)
+    }
+
+    fn has_chromatic_media(&self) -> bool {
+        self.media
+    }
 }
@@ -1,17 +1,25 @@
 //camera.rs
+use crate::aabb::Aabb;
+use crate::atmosphere::{Atmosphere, AtmosphereEvent};
 use crate::color::Color;
+use crate::exposure::{PhysicalExposure, ShutterCurve};
+use crate::filter::{Filter, FilterMode, FilterSampler};
 use crate::hittable::{HitRecord, Hittable};
 use crate::hittable_list::HittableList;
 use crate::interval::Interval;
-use crate::material::Material;
+use crate::light::Light;
 use crate::ray;
 use crate::ray::Ray;
 use crate::rtweekend;
+use crate::sky::PreethamSky;
+use crate::spectrum::{SampledSpectrum, SampledWavelengths, SPECTRUM_SAMPLES};
 use crate::sphere::Sphere;
 use crate::vec3;
 use crate::vec3::{Point3, Vec3};
 use crossbeam::thread;
-use std::io::{stdout, Write};
+use std::fs::File;
+use std::io::{self, stdout, BufWriter, Write};
+use std::path::Path;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Condvar;
 use std::sync::{Arc, Mutex};
@@ -37,6 +45,23 @@ pub struct Camera {
     // 景深效果
     pub defocus_angle: f64, // 失焦角度
     pub focus_dist: f64,    // 对焦距离
+    // 快门：光线时间在 [shutter_open, shutter_close] 内按 shutter_curve 采样
+    pub shutter_open: f64,
+    pub shutter_close: f64,
+    pub shutter_curve: ShutterCurve,
+    // 物理曝光模式：设置后由焦距/光圈决定 vfov 与景深，并按 ISO/EV 缩放输出亮度
+    pub physical: Option<PhysicalExposure>,
+    // 像素重建滤波器及样本的使用方式
+    pub filter: Filter,
+    pub filter_mode: FilterMode,
+    // 光谱模式：每条路径采样一组波长，支持色散；默认使用 RGB
+    pub spectral: bool,
+    // 充满整个场景的全局介质（雾、霾），None 为真空
+    pub atmosphere: Option<Arc<Atmosphere>>,
+    // 解析天空与太阳，设置后替代默认的背景渐变，并对太阳做直接光源采样
+    pub sky: Option<Arc<PreethamSky>>,
+    // 参与直接光照采样的光源；面光源还需要同时加入 world
+    pub lights: Vec<Arc<dyn Light>>,
 
     center: Point3,      // Camera center
     pixel00_loc: Point3, // Location of pixel 0, 0
@@ -49,6 +74,8 @@ pub struct Camera {
 
     defocus_disk_u: Vec3, // 失焦盘的水平半径
     defocus_disk_v: Vec3, // 失焦盘的垂直半径
+
+    filter_sampler: Arc<FilterSampler>,
 }
 impl Default for Camera {
     fn default() -> Self {
@@ -65,6 +92,16 @@ impl Default for Camera {
             lookfrom: Point3::new(0.0, 0.0, -1.0),
             lookat: Point3::new(0.0, 0.0, 0.0),
             vup: Vec3::new(0.0, 1.0, 0.0),
+            shutter_open: 0.0,
+            shutter_close: 1.0,
+            shutter_curve: ShutterCurve::default(),
+            physical: None,
+            filter: Filter::default(),
+            filter_mode: FilterMode::default(),
+            spectral: false,
+            atmosphere: None,
+            sky: None,
+            lights: Vec::new(),
             center: Point3::default(),
             pixel00_loc: Point3::default(),
             pixel_delta_u: Vec3::default(),
@@ -74,6 +111,7 @@ impl Default for Camera {
             w: Vec3::default(),
             defocus_disk_u: Vec3::default(),
             defocus_disk_v: Vec3::default(),
+            filter_sampler: Arc::new(FilterSampler::new(&Filter::default())),
         }
     }
 }
@@ -91,6 +129,8 @@ impl Camera {
         // 相机到视口的距离（焦距）
         // let focal_length = (self.lookfrom - self.lookat).length();
 
+        self.vfov = self.effective_vfov();
+
         let theta = rtweekend::degrees_to_radians(self.vfov);
         let h = (theta / 2.0).tan();
         let viewport_height = 2.0 * h * self.focus_dist;
@@ -113,25 +153,102 @@ impl Camera {
             self.center - (self.focus_dist * self.w) - viewport_u / 2.0 - viewport_v / 2.0;
         // (0,0)像素的中心位置
         self.pixel00_loc = viewport_upper_left + 0.5 * (self.pixel_delta_u + self.pixel_delta_v);
-        let defocus_radius =
-            self.focus_dist * (rtweekend::degrees_to_radians(self.defocus_angle / 2.0)).tan();
+        let defocus_radius = match &self.physical {
+            Some(physical) => physical.aperture_radius(),
+            None => {
+                self.focus_dist * (rtweekend::degrees_to_radians(self.defocus_angle / 2.0)).tan()
+            }
+        };
         self.defocus_disk_u = self.u * defocus_radius;
         self.defocus_disk_v = self.v * defocus_radius;
+
+        self.filter_sampler = Arc::new(FilterSampler::new(&self.filter));
+    }
+
+    /// 保持当前观察方向，调整 lookat/lookfrom 使包围盒完整落在画面内，
+    /// margin 为留白倍数（1.0 表示包围球恰好贴边），同时将焦点设在包围盒中心
+    pub fn fit_to_aabb(&mut self, bbox: &Aabb, margin: f64) {
+        let center = (bbox.min() + bbox.max()) * 0.5;
+        let radius = 0.5 * (bbox.max() - bbox.min()).length();
+
+        let offset = self.lookfrom - self.lookat;
+        let dir = if offset.near_zero() {
+            Vec3::new(0.0, 0.0, 1.0)
+        } else {
+            offset.unit_vector()
+        };
+
+        // 取垂直和水平视野中较小者，保证包围球在两个方向上都不被裁切
+        let vfov = rtweekend::degrees_to_radians(self.effective_vfov());
+        let hfov = 2.0 * ((vfov / 2.0).tan() * self.aspect_ratio).atan();
+        let half_fov = 0.5 * vfov.min(hfov);
+        let dist = radius * margin / half_fov.sin();
+
+        self.lookat = center;
+        self.lookfrom = center + dist * dir;
+        self.focus_dist = dist;
+    }
+
+    /// 按整个场景的包围盒取景
+    pub fn fit_to_world(&mut self, world: &dyn Hittable, margin: f64) {
+        self.fit_to_aabb(&world.bounding_box(), margin);
+    }
+
+    /// 自动对焦：从相机中心经过像素 (i, j) 中心投射光线，将 focus_dist 设为命中点
+    /// 到相机平面的距离；未命中时保持不变并返回 None
+    pub fn auto_focus(&mut self, world: &dyn Hittable, i: i32, j: i32) -> Option<f64> {
+        self.initialize();
+        let pixel_center =
+            self.pixel00_loc + (i as f64 * self.pixel_delta_u) + (j as f64 * self.pixel_delta_v);
+        let r = Ray::new(self.center, pixel_center - self.center, self.shutter_open);
+
+        let mut rec = HitRecord::default();
+        if !world.hit(&r, &Interval::new(0.001, rtweekend::INFINITY), &mut rec) {
+            return None;
+        }
+        self.focus_dist = vec3::dot(rec.p - self.center, -self.w);
+        Some(self.focus_dist)
+    }
+
+    /// 对焦到画面中心
+    pub fn auto_focus_center(&mut self, world: &dyn Hittable) -> Option<f64> {
+        self.initialize();
+        self.auto_focus(world, self.image_width / 2, self.image_height / 2)
+    }
+
+    // 物理曝光模式下 vfov 由焦距决定
+    fn effective_vfov(&self) -> f64 {
+        self.physical
+            .as_ref()
+            .map_or(self.vfov, PhysicalExposure::vfov)
     }
 
+    /// 渲染并以 PPM 格式输出到 stdout
     pub fn render(&mut self, world: &dyn Hittable) {
+        let image = self.render_image(world);
+        let stdout = stdout();
+        write_ppm(&image, &mut stdout.lock()).unwrap();
+
+        eprintln!("\nDone.");
+    }
+
+    /// 渲染并将 PPM 图像写入文件
+    pub fn render_to_file(&mut self, world: &dyn Hittable, path: &Path) -> io::Result<()> {
+        let image = self.render_image(world);
+        let mut out = BufWriter::new(File::create(path)?);
+        write_ppm(&image, &mut out)?;
+        out.flush()
+    }
+
+    /// 渲染并返回像素缓冲区（已按采样数平均并乘以曝光系数，未做 gamma）
+    pub fn render_image(&mut self, world: &dyn Hittable) -> Vec<Vec<Color>> {
         self.initialize();
         let progress = Arc::new(AtomicUsize::new(0));
         let total_lines = self.image_height as usize;
 
-        // 输出 PPM 头部
-        println!("P3\n{} {}\n255", self.image_width, self.image_height);
-        let stdout = stdout();
-
-        // 初始化图像缓冲区
-        let mut image: Vec<Vec<Color>> =
-            vec![vec![Color::default(); self.image_width as usize]; self.image_height as usize];
-        let image_mutex = Arc::new(Mutex::new(&mut image));
+        // 初始化带权重的图像缓冲区
+        let film = Film::new(0, 0, self.image_width as usize, self.image_height as usize);
+        let image_mutex = Arc::new(Mutex::new(film));
 
         // 包装 Camera 和 world
         let camera = Arc::new(self.clone());
@@ -187,23 +304,19 @@ impl Camera {
         })
         .unwrap();
 
-        // 输出图像
-        for j in 0..self.image_height {
-            eprintln!("\rScanlines remaining: {}", self.image_height - j);
-            for i in 0..self.image_width {
-                image[j as usize][i as usize]
-                    .write_color(&mut stdout.lock(), self.samples_per_pixel)
-                    .unwrap();
-            }
-        }
-
-        eprintln!("\nDone.");
+        // 按滤波器权重归一化并应用曝光
+        let film = Arc::try_unwrap(image_mutex)
+            .ok()
+            .expect("render threads still hold the film")
+            .into_inner()
+            .unwrap();
+        film.resolve(self.exposure_scale())
     }
 
     fn render_sub(
         &self,
         world: &dyn Hittable,
-        image_mutex: &Arc<Mutex<&mut Vec<Vec<Color>>>>,
+        image_mutex: &Arc<Mutex<Film>>,
         x_min: usize,
         x_max: usize,
         y_min: usize,
@@ -215,19 +328,53 @@ impl Camera {
         // 限制边界
         let x_max = x_max.min(self.image_width as usize);
         let y_max = y_max.min(self.image_height as usize);
+        // 分块向上取整时末尾的块可能完全落在图像外
+        if x_min >= x_max || y_min >= y_max {
+            progress.fetch_add(1, Ordering::SeqCst);
+            return;
+        }
 
-        // 临时缓冲区
-        let mut buffer = vec![vec![Color::default(); x_max - x_min]; y_max - y_min];
+        // 临时缓冲区，溅射模式下四周留出滤波器半径的边
+        let pad = match self.filter_mode {
+            FilterMode::ImportanceSample => 0,
+            FilterMode::Splat => (self.filter.radius() - 0.5).max(0.0).ceil() as i64,
+        };
+        let mut buffer = Film::new(
+            x_min as i64 - pad,
+            y_min as i64 - pad,
+            x_max - x_min + 2 * pad as usize,
+            y_max - y_min + 2 * pad as usize,
+        );
 
         // 渲染子区域
         for j in y_min..y_max {
             for i in x_min..x_max {
-                let mut pixel_color = Color::default();
+                let (x, y) = (i as i64, j as i64);
                 for _ in 0..self.samples_per_pixel {
-                    let r = self.get_ray(i as i32, j as i32);
-                    pixel_color += Self::ray_color(self, &r, self.max_depth, world);
+                    match self.filter_mode {
+                        FilterMode::ImportanceSample => {
+                            let (dx, dy, weight) = self.filter_sampler.sample();
+                            let r = self.get_ray(i as i32, j as i32, dx, dy);
+                            let color = self.sample_color(&r, world);
+                            buffer.add(x, y, color, weight);
+                        }
+                        FilterMode::Splat => {
+                            let dx = rtweekend::random_double() - 0.5;
+                            let dy = rtweekend::random_double() - 0.5;
+                            let r = self.get_ray(i as i32, j as i32, dx, dy);
+                            let color = self.sample_color(&r, world);
+                            for ny in -pad..=pad {
+                                for nx in -pad..=pad {
+                                    let weight =
+                                        self.filter.evaluate(nx as f64 - dx, ny as f64 - dy);
+                                    if weight != 0.0 {
+                                        buffer.add(x + nx, y + ny, color, weight);
+                                    }
+                                }
+                            }
+                        }
+                    }
                 }
-                buffer[j - y_min][i - x_min] = pixel_color;
             }
         }
         // 实时进度更新（每渲染完一行调用）
@@ -237,41 +384,259 @@ impl Camera {
             total_lines.saturating_sub(done)
         );
         std::io::stderr().flush().unwrap();
-        // 将缓冲区写入图像
-        let mut image = image_mutex.lock().unwrap();
-        for j in y_min..y_max {
-            for i in x_min..x_max {
-                image[j][i] = buffer[j - y_min][i - x_min];
-            }
+        // 将缓冲区累加到图像
+        image_mutex.lock().unwrap().merge(&buffer);
+    }
+
+    /// 相机光线的颜色样本，按 spectral 选择 RGB 或光谱路径
+    fn sample_color(&self, r: &Ray, world: &dyn Hittable) -> Color {
+        if !self.spectral {
+            return self.ray_color(r, self.max_depth, world, true);
         }
+        let lambdas = SampledWavelengths::random();
+        let r = r.with_wavelength(Some(lambdas.hero()));
+        let radiance = self.ray_spectrum(&r, self.max_depth, world, lambdas, true);
+        lambdas.to_rgb(&radiance)
     }
 
-    fn ray_color(&self, r: &Ray, depth: i32, world: &dyn Hittable) -> Color {
+    // specular 表示上一次散射没有做光源采样（相机光线、镜面等），此时光线看到的太阳要计入；
+    // 否则太阳已经由 sample_direct 计入，不能重复
+    fn ray_color(&self, r: &Ray, depth: i32, world: &dyn Hittable, specular: bool) -> Color {
         let mut rec = HitRecord::default();
         if depth <= 0 {
             return Color::new(0.0, 0.0, 0.0);
         }
-        if !world.hit(r, &Interval::new(0.001, rtweekend::INFINITY), &mut rec) {
-            let unit_direction = r.direction().unit_vector();
-            let a = 0.35 + 0.5 * (unit_direction.x + 1.0);
-            let start_color = Color::new(1.0, 1.0, 1.0);
-            let end_color = Color::new(1.0, 0.3, 0.0);
-            return (1.0 - a) * start_color + a * end_color;
+        let hit = world.hit(r, &Interval::new(0.001, rtweekend::INFINITY), &mut rec);
+
+        // 全局介质：先在到达表面（或无穷远）之前采样一次自由程
+        let t_max = if hit { rec.t } else { rtweekend::INFINITY };
+        let mut transmittance = Color::new(1.0, 1.0, 1.0);
+        if let Some(atmosphere) = &self.atmosphere {
+            match atmosphere.sample(r, t_max) {
+                AtmosphereEvent::Scatter { scattered, weight } => {
+                    let phase = |dir: Vec3| {
+                        let p = atmosphere.phase.eval(r.direction(), dir);
+                        Some(Color::new(p, p, p))
+                    };
+                    let direct = self.sample_direct(&scattered, world, phase);
+                    let indirect = self.ray_color(&scattered, depth - 1, world, false);
+                    let weight = weight * Self::media_weight(world, r, &scattered);
+                    return weight * (direct.unwrap_or_default() + indirect);
+                }
+                AtmosphereEvent::Pass { weight } => transmittance = weight,
+            }
+        }
+        // 场景中彩色介质（ConstantMedium）对这一段的按通道修正
+        let transmittance = transmittance * world.transmittance(r, &Interval::new(0.001, t_max));
+        if !hit {
+            return transmittance * self.background(r, specular);
         }
 
         let mut scattered = Ray::default();
         let mut attenuation = Color::default();
-        let color_from_emission = rec.mat.emitted(rec.u, rec.v, &rec.p);
+        let color_from_emission = if specular || !self.light_sampled(&rec) {
+            rec.mat.emitted(r, &rec)
+        } else {
+            Color::default()
+        };
         if !rec.mat.scatter(r, &rec, &mut attenuation, &mut scattered) {
-            return color_from_emission;
+            return transmittance * color_from_emission;
+        }
+        let direct = self.sample_direct(&scattered, world, |dir| rec.mat.eval(r, &rec, dir));
+        let color_from_scatter =
+            attenuation * self.ray_color(&scattered, depth - 1, world, direct.is_none());
+        transmittance * (color_from_emission + direct.unwrap_or_default() + color_from_scatter)
+    }
+
+    // 与 ray_color 相同的路径，但在各采样波长上累积；RGB 的衰减和自发光按光谱上采样
+    fn ray_spectrum(
+        &self,
+        r: &Ray,
+        depth: i32,
+        world: &dyn Hittable,
+        lambdas: SampledWavelengths,
+        specular: bool,
+    ) -> SampledSpectrum {
+        let mut rec = HitRecord::default();
+        if depth <= 0 {
+            return [0.0; SPECTRUM_SAMPLES];
+        }
+        let hit = world.hit(r, &Interval::new(0.001, rtweekend::INFINITY), &mut rec);
+
+        let t_max = if hit { rec.t } else { rtweekend::INFINITY };
+        let media = world.transmittance(r, &Interval::new(0.001, t_max));
+        let mut transmittance = lambdas.upsample(media);
+        if let Some(atmosphere) = &self.atmosphere {
+            match atmosphere.sample(r, t_max) {
+                AtmosphereEvent::Scatter { scattered, weight } => {
+                    let phase = |dir: Vec3| {
+                        let p = atmosphere.phase.eval(r.direction(), dir);
+                        Some(Color::new(p, p, p))
+                    };
+                    let direct = self.sample_direct(&scattered, world, phase);
+                    let mut radiance =
+                        self.ray_spectrum(&scattered, depth - 1, world, lambdas, false);
+                    let direct = lambdas.upsample(direct.unwrap_or_default());
+                    let weight = weight * Self::media_weight(world, r, &scattered);
+                    for ((l, d), w) in radiance
+                        .iter_mut()
+                        .zip(direct)
+                        .zip(lambdas.upsample(weight))
+                    {
+                        *l = w * (*l + d);
+                    }
+                    return radiance;
+                }
+                AtmosphereEvent::Pass { weight } => {
+                    transmittance = lambdas.upsample(weight * media)
+                }
+            }
+        }
+        let mut radiance = if hit {
+            self.shade_spectrum(r, &rec, depth, world, lambdas, specular)
+        } else {
+            lambdas.upsample(self.background(r, specular))
+        };
+        for (l, w) in radiance.iter_mut().zip(transmittance) {
+            *l *= w;
         }
-        let color_from_scatter = attenuation * self.ray_color(&scattered, depth - 1, world);
-        color_from_emission + color_from_scatter
+        radiance
     }
-    /// 在像素区域内随机采样（用于抗锯齿）
-    fn pixel_sample_square(&self) -> Vec3 {
-        let px = -0.5 + rtweekend::random_double();
-        let py = -0.5 + rtweekend::random_double();
+
+    // 表面命中处的光谱着色（自发光加散射）
+    fn shade_spectrum(
+        &self,
+        r: &Ray,
+        rec: &HitRecord,
+        depth: i32,
+        world: &dyn Hittable,
+        lambdas: SampledWavelengths,
+        specular: bool,
+    ) -> SampledSpectrum {
+        let mut scattered = Ray::default();
+        let mut attenuation = Color::default();
+        let mut radiance = if specular || !self.light_sampled(rec) {
+            lambdas.upsample(rec.mat.emitted(r, rec))
+        } else {
+            [0.0; SPECTRUM_SAMPLES]
+        };
+        if !rec.mat.scatter(r, rec, &mut attenuation, &mut scattered) {
+            return radiance;
+        }
+        let scattered = scattered.with_wavelength(Some(lambdas.hero()));
+        let direct = self.sample_direct(&scattered, world, |dir| rec.mat.eval(r, rec, dir));
+        let next_specular = direct.is_none();
+
+        // 色散后的方向只对 hero 波长成立，后续路径只保留 hero
+        let incoming = if rec.mat.dispersive() && !lambdas.secondary_terminated() {
+            let terminated = lambdas.terminate_secondary();
+            SampledWavelengths::hero_only(self.ray_spectrum(
+                &scattered,
+                depth - 1,
+                world,
+                terminated,
+                next_specular,
+            ))
+        } else {
+            self.ray_spectrum(&scattered, depth - 1, world, lambdas, next_specular)
+        };
+        let attenuation = lambdas.upsample(attenuation);
+        let direct = lambdas.upsample(direct.unwrap_or_default());
+        for (((l, a), i), d) in radiance
+            .iter_mut()
+            .zip(attenuation)
+            .zip(incoming)
+            .zip(direct)
+        {
+            *l += a * i + d;
+        }
+        radiance
+    }
+
+    // 命中点是否是 lights 中某个面光源的发光面：做过光源采样的路径再命中时不重复计入自发光
+    fn light_sampled(&self, rec: &HitRecord) -> bool {
+        self.lights.iter().any(|light| light.emits(&rec.mat))
+    }
+
+    // 从散射点（scattered 的起点）对太阳和 lights 中的每个光源各采样一次，
+    // eval 给出散射到该方向的 BSDF·cos 或相函数值。没有可采样的光源或 eval 返回 None（镜面等）时
+    // 返回 None，由下一次 BSDF 采样计入光照
+    fn sample_direct(
+        &self,
+        scattered: &Ray,
+        world: &dyn Hittable,
+        eval: impl Fn(Vec3) -> Option<Color>,
+    ) -> Option<Color> {
+        if self.sky.is_none() && self.lights.is_empty() {
+            return None;
+        }
+        // 先确认材质能按方向求值，否则即使所有光源都照不到也要按镜面处理
+        eval(scattered.direction())?;
+
+        let p = scattered.origin();
+        let sun = self.sky.iter().map(|sky| sky.as_ref() as &dyn Light);
+        let lights = self.lights.iter().map(|light| light.as_ref());
+        let mut total = Color::default();
+        for light in sun.chain(lights) {
+            let Some(sample) = light.sample(&p) else {
+                continue;
+            };
+            let f = eval(sample.direction)?;
+            if f.near_zero() || sample.radiance.near_zero() {
+                continue;
+            }
+            // 阴影光线止于光源之前，避免与面光源自身相交
+            let shadow = Ray::new(p, sample.direction, scattered.time());
+            let mut rec = HitRecord::default();
+            let shadow_t = Interval::new(0.001, sample.distance - 0.001);
+            // 介质的真实碰撞也会遮挡，彩色介质各通道的差异再由 transmittance 修正
+            if world.hit(&shadow, &shadow_t, &mut rec) {
+                continue;
+            }
+            let mut transmittance = world.transmittance(&shadow, &shadow_t);
+            if let Some(atmosphere) = &self.atmosphere {
+                transmittance = transmittance * atmosphere.transmittance(&shadow, sample.distance);
+            }
+            total += f * sample.radiance * transmittance;
+        }
+        Some(total)
+    }
+
+    // 光线 r 在全局介质中散射为 scattered 时，场景中彩色介质对散射点之前那一段的按通道修正
+    fn media_weight(world: &dyn Hittable, r: &Ray, scattered: &Ray) -> Color {
+        let t = (scattered.origin() - r.origin()).length() / r.direction().length();
+        world.transmittance(r, &Interval::new(0.001, t))
+    }
+
+    // 未击中物体时的背景：设置了天空模型时使用天空，否则为默认渐变
+    fn background(&self, r: &Ray, specular: bool) -> Color {
+        match &self.sky {
+            Some(sky) => sky.radiance(r.direction(), specular),
+            None => Self::background_color(r),
+        }
+    }
+
+    // 未击中物体时的背景渐变
+    fn background_color(r: &Ray) -> Color {
+        let unit_direction = r.direction().unit_vector();
+        let a = 0.35 + 0.5 * (unit_direction.x + 1.0);
+        let start_color = Color::new(1.0, 1.0, 1.0);
+        let end_color = Color::new(1.0, 0.3, 0.0);
+        (1.0 - a) * start_color + a * end_color
+    }
+    /// 输出亮度缩放：物理曝光模式下由光圈、快门速度、ISO 和曝光补偿决定
+    fn exposure_scale(&self) -> f64 {
+        self.physical
+            .as_ref()
+            .map_or(1.0, PhysicalExposure::exposure_scale)
+    }
+    /// 按快门曲线在 [shutter_open, shutter_close] 内采样光线时间
+    fn sample_time(&self) -> f64 {
+        let s = self.shutter_curve.sample(rtweekend::random_double());
+        self.shutter_open + s * (self.shutter_close - self.shutter_open)
+    }
+    /// 像素中心偏移 (px, py) 对应的视口偏移（用于抗锯齿）
+    fn pixel_sample_square(&self, px: f64, py: f64) -> Vec3 {
         px * self.pixel_delta_u + py * self.pixel_delta_v
     }
     /// 在失焦盘上随机采样（用于景深效果）
@@ -280,19 +645,178 @@ impl Camera {
         self.center + (p.x * self.defocus_disk_u) + (p.y * self.defocus_disk_v)
     }
 
-    fn get_ray(&self, i: i32, j: i32) -> Ray {
+    /// 经过像素 (i, j) 中心偏移 (dx, dy) 处的相机光线
+    fn get_ray(&self, i: i32, j: i32, dx: f64, dy: f64) -> Ray {
         let pixel_center =
             self.pixel00_loc + (i as f64 * self.pixel_delta_u) + (j as f64 * self.pixel_delta_v);
-        let pixel_sample = pixel_center + self.pixel_sample_square();
+        let pixel_sample = pixel_center + self.pixel_sample_square(dx, dy);
         let ray_origin = {
-            if self.defocus_angle <= 0.0 {
+            if self.defocus_angle <= 0.0 && self.physical.is_none() {
                 self.center
             } else {
                 self.defocus_disk_sample()
             }
         };
         let ray_direc = pixel_sample - ray_origin;
-        let ray_tm = rtweekend::random_double();
+        let ray_tm = self.sample_time();
         Ray::new(ray_origin, ray_direc, ray_tm)
     }
 }
+
+/// 以 PPM (P3) 格式写出像素缓冲区
+pub fn write_ppm(image: &[Vec<Color>], out: &mut dyn Write) -> io::Result<()> {
+    let height = image.len();
+    let width = image.first().map_or(0, |row| row.len());
+    writeln!(out, "P3\n{} {}\n255", width, height)?;
+    for row in image {
+        for pixel in row {
+            pixel.write_color(out, 1)?;
+        }
+    }
+    Ok(())
+}
+
+// 带滤波器权重的像素累积缓冲区，(x0, y0) 为缓冲区左上角在整幅图像中的坐标
+struct Film {
+    x0: i64,
+    y0: i64,
+    width: usize,
+    height: usize,
+    color: Vec<Color>,
+    weight: Vec<f64>,
+}
+
+impl Film {
+    fn new(x0: i64, y0: i64, width: usize, height: usize) -> Self {
+        Self {
+            x0,
+            y0,
+            width,
+            height,
+            color: vec![Color::default(); width * height],
+            weight: vec![0.0; width * height],
+        }
+    }
+
+    fn index(&self, x: i64, y: i64) -> Option<usize> {
+        let (lx, ly) = (x - self.x0, y - self.y0);
+        if lx < 0 || ly < 0 || lx >= self.width as i64 || ly >= self.height as i64 {
+            return None;
+        }
+        Some(ly as usize * self.width + lx as usize)
+    }
+
+    // 超出缓冲区范围的样本直接丢弃
+    fn add(&mut self, x: i64, y: i64, color: Color, weight: f64) {
+        if let Some(idx) = self.index(x, y) {
+            self.color[idx] += weight * color;
+            self.weight[idx] += weight;
+        }
+    }
+
+    fn merge(&mut self, tile: &Film) {
+        for ty in 0..tile.height {
+            for tx in 0..tile.width {
+                let t_idx = ty * tile.width + tx;
+                if tile.weight[t_idx] == 0.0 {
+                    continue;
+                }
+                let (x, y) = (tile.x0 + tx as i64, tile.y0 + ty as i64);
+                if let Some(idx) = self.index(x, y) {
+                    self.color[idx] += tile.color[t_idx];
+                    self.weight[idx] += tile.weight[t_idx];
+                }
+            }
+        }
+    }
+
+    fn resolve(&self, scale: f64) -> Vec<Vec<Color>> {
+        (0..self.height)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| {
+                        let idx = y * self.width + x;
+                        if self.weight[idx] > 0.0 {
+                            self.color[idx] * (scale / self.weight[idx])
+                        } else {
+                            Color::default()
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::light::{AreaLight, PointLight};
+    use crate::material::{Material, NormalMapped, Principled};
+    use crate::quad::Quad;
+    use crate::texture::SolidColor;
+
+    // 点光源只能通过光源采样照亮表面，glTF 导入的 Principled（以及法线贴图包装）必须能按方向求值
+    #[test]
+    fn point_light_illuminates_principled() {
+        let principled: Arc<dyn Material + Send + Sync> =
+            Arc::new(Principled::new(Color::new(0.8, 0.8, 0.8), 0.0, 0.5));
+        let flat = Arc::new(SolidColor::new(Color::new(0.5, 0.5, 1.0)));
+        let materials: [Arc<dyn Material + Send + Sync>; 2] = [
+            principled.clone(),
+            Arc::new(NormalMapped::new(principled, flat, 1.0)),
+        ];
+        for mat in materials {
+            let mut world = HittableList::default();
+            world.add(Arc::new(Quad::new(
+                Point3::new(-1.0, 0.0, -1.0),
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.0, 2.0),
+                mat.clone(),
+            )));
+            let mut cam = Camera::default();
+            let light = PointLight::new(Point3::new(0.0, 2.0, 0.0), Color::new(1.0, 1.0, 1.0), 4.0);
+            cam.lights.push(Arc::new(light));
+
+            // 深度 1：只剩直接光照，点光源的结果是确定的 eval · I / d²
+            let r = Ray::new(Point3::new(0.0, 1.0, 1.0), Vec3::new(0.0, -1.0, -1.0), 0.0);
+            let radiance = cam.ray_color(&r, 1, &world, true);
+
+            let mut rec = HitRecord::default();
+            assert!(world.hit(&r, &Interval::new(0.001, rtweekend::INFINITY), &mut rec));
+            let to_light = Point3::new(0.0, 2.0, 0.0) - rec.p;
+            let f = mat.eval(&r, &rec, vec3::unit_vector(to_light)).unwrap();
+            let expected = f * 4.0 / to_light.length_squared();
+            assert!(expected.x > 0.0);
+            assert!(
+                (radiance - expected).near_zero(),
+                "{radiance:?} vs {expected:?}"
+            );
+        }
+    }
+
+    // 只有加入 lights 的那个面光源在非镜面路径上不重复计入，同样的灯只放进 world 时照常发光
+    #[test]
+    fn area_light_emission_skipped_only_when_sampled() {
+        let quad = || {
+            AreaLight::quad(
+                Point3::new(-1.0, 1.0, -1.0),
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.0, 2.0),
+                Color::new(1.0, 1.0, 1.0),
+            )
+        };
+        let sampled = Arc::new(quad());
+        let unsampled = Arc::new(quad());
+        let mut cam = Camera::default();
+        cam.lights.push(sampled.clone());
+
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), 0.0);
+        for (light, expected) in [(sampled, 0.0), (unsampled, 1.0)] {
+            let mut world = HittableList::default();
+            world.add(light);
+            assert_eq!(cam.ray_color(&r, 1, &world, false).x, expected);
+            assert_eq!(cam.ray_color(&r, 1, &world, true).x, 1.0);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//conductor.rs
+// 常见金属的实测复折射率 eta + i·k，按波长制表（nm），取自 Johnson & Christy (1972)、
+// Rakić (1995/1998) 等公开数据，在可见光范围内按 50nm 重采样
+use crate::color::Color;
+use crate::spectrum;
+
+const WAVELENGTHS: [f64; 9] = [
+    380.0, 430.0, 480.0, 530.0, 580.0, 630.0, 680.0, 730.0, 780.0,
+];
+
+/// 制表的导体光谱数据
+#[derive(Debug, PartialEq)]
+pub struct ConductorSpectrum {
+    pub name: &'static str,
+    eta: [f64; 9],
+    k: [f64; 9],
+}
+
+pub static GOLD: ConductorSpectrum = ConductorSpectrum {
+    name: "gold",
+    eta: [1.70, 1.52, 1.19, 0.57, 0.26, 0.19, 0.16, 0.16, 0.17],
+    k: [1.90, 1.85, 1.82, 2.16, 2.79, 3.27, 3.75, 4.21, 4.65],
+};
+
+pub static SILVER: ConductorSpectrum = ConductorSpectrum {
+    name: "silver",
+    eta: [0.19, 0.15, 0.13, 0.12, 0.12, 0.13, 0.14, 0.15, 0.16],
+    k: [1.82, 2.38, 2.86, 3.28, 3.66, 4.07, 4.47, 4.87, 5.28],
+};
+
+pub static COPPER: ConductorSpectrum = ConductorSpectrum {
+    name: "copper",
+    eta: [1.22, 1.18, 1.15, 1.05, 0.56, 0.25, 0.21, 0.22, 0.26],
+    k: [2.13, 2.31, 2.49, 2.58, 2.77, 3.42, 3.94, 4.41, 4.84],
+};
+
+pub static ALUMINUM: ConductorSpectrum = ConductorSpectrum {
+    name: "aluminum",
+    eta: [0.44, 0.56, 0.71, 0.89, 1.11, 1.39, 1.75, 2.26, 2.66],
+    k: [4.60, 5.23, 5.84, 6.45, 7.04, 7.60, 8.12, 8.50, 8.45],
+};
+
+pub static CHROME: ConductorSpectrum = ConductorSpectrum {
+    name: "chrome",
+    eta: [1.45, 1.70, 2.20, 2.80, 3.15, 3.45, 3.75, 3.95, 4.10],
+    k: [3.60, 3.85, 4.15, 4.35, 4.40, 4.40, 4.40, 4.35, 4.30],
+};
+
+pub static IRON: ConductorSpectrum = ConductorSpectrum {
+    name: "iron",
+    eta: [2.30, 2.55, 2.80, 2.93, 2.95, 2.92, 2.88, 2.85, 2.82],
+    k: [2.60, 2.75, 2.88, 2.93, 3.00, 3.10, 3.25, 3.38, 3.50],
+};
+
+pub static TITANIUM: ConductorSpectrum = ConductorSpectrum {
+    name: "titanium",
+    eta: [2.05, 2.20, 2.35, 2.50, 2.62, 2.72, 2.84, 2.98, 3.10],
+    k: [2.80, 2.98, 3.16, 3.36, 3.56, 3.76, 3.92, 4.03, 4.12],
+};
+
+/// 所有内置导体，供按名字查找
+pub static PRESETS: [&ConductorSpectrum; 7] = [
+    &GOLD, &SILVER, &COPPER, &ALUMINUM, &CHROME, &IRON, &TITANIUM,
+];
+
+impl ConductorSpectrum {
+    /// 按名字（不区分大小写）查找内置导体，如 "gold"、"Copper"
+    pub fn from_name(name: &str) -> Option<&'static ConductorSpectrum> {
+        PRESETS
+            .iter()
+            .copied()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// 波长 lambda_nm 处的 (eta, k)，表内线性插值，超出范围取端点
+    pub fn eta_k(&self, lambda_nm: f64) -> (f64, f64) {
+        let last = WAVELENGTHS.len() - 1;
+        let pos = ((lambda_nm - WAVELENGTHS[0]) / (WAVELENGTHS[1] - WAVELENGTHS[0]))
+            .clamp(0.0, last as f64);
+        let i = (pos as usize).min(last - 1);
+        let t = pos - i as f64;
+        let lerp = |table: &[f64; 9]| table[i] * (1.0 - t) + table[i + 1] * t;
+        (lerp(&self.eta), lerp(&self.k))
+    }
+
+    /// 按 RGB 通道的 (eta, k)，由光谱向 RGB 上采样基函数投影得到，用于 RGB 模式
+    pub fn eta_k_rgb(&self) -> (Color, Color) {
+        (
+            spectrum::project_to_rgb(|l| self.eta_k(l).0),
+            spectrum::project_to_rgb(|l| self.eta_k(l).1),
+        )
+    }
+}
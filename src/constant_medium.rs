@@ -1,19 +1,32 @@
 //constant_medium.rs
+// 边界内的参与介质。密度由 DensityField 给出（常数或随空间变化），
+// 按 majorant 做 delta tracking：先以上界采样候选碰撞，再按真实消光与上界之比决定是否为真实碰撞，
+// 密度场可以按区段给出更紧的局部上界（体素网格的 majorant 网格）。
+// 各通道消光不同时，hit 按平均消光采样真实碰撞，各通道的差异由 transmittance 给出的权重修正
 use std::sync::Arc;
 
 use crate::aabb::Aabb;
+use crate::color::Color;
+use crate::density::{DensityField, UniformDensity};
 use crate::hittable::{HitRecord, Hittable};
 use crate::interval::Interval;
-use crate::material::{Isotropic, Material};
+use crate::material::{Anisotropic, Isotropic, Material};
+use crate::phase::PhaseFunction;
 use crate::ray::Ray;
 use crate::rtweekend::{random_double, INFINITY};
-use crate::texture::{SolidColor, Texture};
-use crate::vec3::{Point3, Vec3};
+use crate::texture::{ScaledTexture, SolidColor, Texture};
+use crate::vec3::Vec3;
 
 pub struct ConstantMedium {
     boundary: Arc<dyn Hittable + Send + Sync>,
-    neg_inv_density: f64,
-    phase_function: Arc<dyn Material + Send + Sync>,
+    density: Arc<dyn DensityField>,
+    sigma_a: Color, // 单位密度下的吸收系数
+    sigma_s: Color, // 单位密度下的散射系数
+    albedo: Arc<dyn Texture + Send + Sync>,
+    phase: Option<Arc<dyn PhaseFunction>>, // None 为各向同性
+    emission: Option<Arc<dyn Texture>>,    // 自发光辐亮度 Le(p)
+    phase_function: Arc<dyn Material + Send + Sync>, // 真实碰撞时的散射材质，权重已折算进纹理
+    gray: bool, // 各通道消光相同时，真实碰撞概率恰为 σt / majorant，不需要额外权重
 }
 
 impl ConstantMedium {
@@ -23,11 +36,17 @@ impl ConstantMedium {
         density: f64,
         texture: Arc<dyn Texture + Send + Sync>,
     ) -> Self {
-        Self {
+        Self::build(Self {
             boundary,
-            neg_inv_density: -1.0 / density,
-            phase_function: Arc::new(Isotropic::new_from_texture(texture)),
-        }
+            density: Arc::new(UniformDensity::new(density)),
+            sigma_a: Color::default(),
+            sigma_s: Color::new(1.0, 1.0, 1.0),
+            albedo: texture,
+            phase: None,
+            emission: None,
+            phase_function: Arc::new(Isotropic::new_from_color(Color::default())),
+            gray: true,
+        })
     }
 
     // 构造一个使用纯色的常密度体
@@ -39,16 +58,108 @@ impl ConstantMedium {
         let texture = Arc::new(SolidColor::new(color));
         Self::new_with_texture(boundary, density, texture)
     }
+
+    // 构造非均匀介质：消光系数为 density(p) · (σa + σs)，各通道可以不同
+    pub fn new_heterogeneous(
+        boundary: Arc<dyn Hittable + Send + Sync>,
+        density: Arc<dyn DensityField>,
+        sigma_a: Color,
+        sigma_s: Color,
+    ) -> Self {
+        Self::build(Self {
+            density,
+            sigma_a,
+            sigma_s,
+            ..Self::new_with_color(boundary, 1.0, Color::new(1.0, 1.0, 1.0))
+        })
+    }
+
+    // 使用各向异性相函数（Henyey–Greenstein 等）
+    pub fn with_phase(self, phase: Arc<dyn PhaseFunction>) -> Self {
+        Self::build(Self {
+            phase: Some(phase),
+            ..self
+        })
+    }
+
+    // 介质自发光（火焰）：真实碰撞处按吸收占比累加 σa / σt · Le(p)，σa 为 0 时不发光
+    pub fn with_emission(self, emission: Arc<dyn Texture>) -> Self {
+        Self::build(Self {
+            emission: Some(emission),
+            ..self
+        })
+    }
+
+    // 真实碰撞的权重与密度无关：灰色介质为 σs / σt，彩色介质为 σs / mean(σt)，
+    // 因此可以预先折算进散射材质的纹理；自发光的权重同理为 σa / σt 或 σa / mean(σt)
+    fn build(mut medium: Self) -> Self {
+        let sigma_t = medium.sigma_a + medium.sigma_s;
+        medium.gray = sigma_t.x == sigma_t.y && sigma_t.y == sigma_t.z;
+        let inv = if medium.gray {
+            if sigma_t.x > 0.0 {
+                1.0 / sigma_t.x
+            } else {
+                0.0
+            }
+        } else {
+            3.0 / (sigma_t.x + sigma_t.y + sigma_t.z)
+        };
+        let scaled: Arc<dyn Texture> = Arc::new(ScaledTexture::new(
+            medium.albedo.clone(),
+            medium.sigma_s * inv,
+        ));
+        let scatter: Arc<dyn Material + Send + Sync> = match &medium.phase {
+            Some(phase) => Arc::new(Anisotropic::new_from_texture(scaled, phase.clone())),
+            None => Arc::new(Isotropic::new_from_texture(scaled)),
+        };
+        medium.phase_function = match &medium.emission {
+            Some(emission) => Arc::new(EmissiveScatter {
+                scatter,
+                emission: emission.clone(),
+                weight: medium.sigma_a * inv,
+            }),
+            None => scatter,
+        };
+        medium
+    }
 }
 
-impl Hittable for ConstantMedium {
-    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &mut HitRecord) -> bool {
+// 带自发光的真实碰撞
+struct EmissiveScatter {
+    scatter: Arc<dyn Material + Send + Sync>,
+    emission: Arc<dyn Texture>,
+    weight: Color,
+}
+
+impl Material for EmissiveScatter {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        self.scatter.scatter(r_in, rec, attenuation, scattered)
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        self.scatter.eval(r_in, rec, direction)
+    }
+
+    fn emitted(&self, _r_in: &Ray, rec: &HitRecord) -> Color {
+        self.weight * self.emission.value(rec.u, rec.v, &rec.p)
+    }
+}
+
+impl ConstantMedium {
+    // 光线在 ray_t 内位于边界中的区段 [t1, t2]，不相交时返回 None
+    fn segment(&self, r: &Ray, ray_t: &Interval) -> Option<(f64, f64)> {
         let mut rec1 = HitRecord::default();
         let mut rec2 = HitRecord::default();
 
         // 第一次命中 boundary
         if !self.boundary.hit(r, &Interval::UNIVERSE, &mut rec1) {
-            return false;
+            return None;
         }
 
         // 第二次命中 boundary（出界）
@@ -56,40 +167,149 @@ impl Hittable for ConstantMedium {
             .boundary
             .hit(r, &Interval::new(rec1.t + 0.0001, INFINITY), &mut rec2)
         {
-            return false;
-        }
-
-        let mut t1 = rec1.t.max(ray_t.min);
-        let mut t2 = rec2.t.min(ray_t.max);
-
-        if t1 >= t2 {
-            return false;
+            return None;
         }
 
-        if t1 < 0.0 {
-            t1 = 0.0;
-        }
+        let t1 = rec1.t.max(ray_t.min).max(0.0);
+        let t2 = rec2.t.min(ray_t.max);
+        (t1 < t2).then_some((t1, t2))
+    }
 
+    // 以各区段的 majorant 在 [t1, t2) 内依次采样候选碰撞，对每个候选点调用 visit(密度, majorant)，
+    // visit 返回 true 时停止并返回该点；越过区段末端时从末端重新采样（指数分布无记忆）
+    fn track(
+        &self,
+        r: &Ray,
+        (t1, t2): (f64, f64),
+        mut visit: impl FnMut(f64, f64) -> bool,
+    ) -> Option<f64> {
+        let sigma_t = self.sigma_a + self.sigma_s;
+        let max_t = sigma_t.x.max(sigma_t.y).max(sigma_t.z);
         let ray_length = r.direction().length();
-        let distance_inside_boundary = (t2 - t1) * ray_length;
-        let hit_distance = self.neg_inv_density * random_double().ln();
+        let mut t = t1;
+        loop {
+            let (max_density, segment_end) = self.density.majorant(r, t);
+            let segment_end = segment_end.min(t2);
+            let majorant = max_density * max_t;
+            let step = if majorant > 0.0 {
+                -random_double().ln() / (majorant * ray_length)
+            } else {
+                INFINITY
+            };
+            if t + step >= segment_end {
+                if segment_end >= t2 {
+                    return None;
+                }
+                t = segment_end;
+                continue;
+            }
+            t += step;
+            if visit(self.density.density(&r.at(t)), majorant) {
+                return Some(t);
+            }
+        }
+    }
+}
 
-        if hit_distance > distance_inside_boundary {
+impl Hittable for ConstantMedium {
+    // 只返回真实碰撞：彩色介质按平均消光决定真实碰撞，各通道与平均值的差异由 transmittance 补上
+    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &mut HitRecord) -> bool {
+        let Some(segment) = self.segment(r, ray_t) else {
             return false;
-        }
+        };
+        let sigma_t = self.sigma_a + self.sigma_s;
+        let real_t = if self.gray {
+            sigma_t.x
+        } else {
+            (sigma_t.x + sigma_t.y + sigma_t.z) / 3.0
+        };
+        let Some(t) = self.track(r, segment, |density, majorant| {
+            random_double() < density * real_t / majorant
+        }) else {
+            return false;
+        };
 
-        rec.t = t1 + hit_distance / ray_length;
-        rec.p = r.at(rec.t);
+        rec.mat = Arc::clone(&self.phase_function);
+        rec.t = t;
+        rec.p = r.at(t);
 
         // 体积散射不关心表面法线与朝向
         rec.normal = Vec3::new(1.0, 0.0, 0.0); // 任意法线
         rec.front_face = true;
-        rec.mat = Arc::clone(&self.phase_function);
-
         true
     }
 
     fn bounding_box(&self) -> Aabb {
         self.boundary.bounding_box()
     }
+
+    // 彩色介质中 hit 未在 ray_t 内给出真实碰撞的概率是 exp(-mean(σt)·∫density)，
+    // 而通道 c 应有的透射率是 exp(-σt_c·∫density)。用一组独立的候选碰撞估计两者之比：
+    // 每个候选点乘以 1 - density·(σt_c - mean(σt)) / majorant，期望恰为 exp(-(σt_c - mean(σt))·∫density)。
+    // 这个估计与 hit 的采样相互独立，因此乘在 hit 的结果上（无论命中的是真实碰撞、其他表面还是什么都没命中）都是无偏的
+    fn transmittance(&self, r: &Ray, ray_t: &Interval) -> Color {
+        let mut weight = Color::new(1.0, 1.0, 1.0);
+        if self.gray {
+            return weight;
+        }
+        let Some(segment) = self.segment(r, ray_t) else {
+            return weight;
+        };
+        let sigma_t = self.sigma_a + self.sigma_s;
+        let mean_t = (sigma_t.x + sigma_t.y + sigma_t.z) / 3.0;
+        let offset = sigma_t - Color::new(mean_t, mean_t, mean_t);
+        self.track(r, segment, |density, majorant| {
+            weight = weight * (Color::new(1.0, 1.0, 1.0) - offset * (density / majorant));
+            false
+        });
+        weight
+    }
+
+    fn has_chromatic_media(&self) -> bool {
+        !self.gray
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sphere::Sphere;
+    use crate::vec3::Point3;
+
+    // 纯吸收的彩色介质：未发生真实碰撞的指示乘以 transmittance 的期望应为各通道的 exp(-σt·d)
+    #[test]
+    fn chromatic_transmittance_is_unbiased() {
+        const SAMPLES: usize = 20_000;
+        let boundary = Arc::new(Sphere::new_static(
+            Point3::new(0.0, 0.0, 0.0),
+            1.0,
+            Arc::new(Isotropic::new_from_color(Color::default())),
+        ));
+        let sigma_a = Color::new(0.2, 0.5, 1.0);
+        let medium = ConstantMedium::new_heterogeneous(
+            boundary,
+            Arc::new(UniformDensity::new(1.0)),
+            sigma_a,
+            Color::default(),
+        );
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0), 0.0);
+        let ray_t = Interval::new(0.001, INFINITY);
+
+        let mut total = Color::default();
+        for _ in 0..SAMPLES {
+            let mut rec = HitRecord::default();
+            if !medium.hit(&r, &ray_t, &mut rec) {
+                total += medium.transmittance(&r, &ray_t);
+            }
+        }
+        let estimate = total / SAMPLES as f64;
+        for (e, s) in [
+            (estimate.x, sigma_a.x),
+            (estimate.y, sigma_a.y),
+            (estimate.z, sigma_a.z),
+        ] {
+            let expected = (-2.0 * s).exp();
+            assert!((e - expected).abs() < 0.02, "{e} vs {expected}");
+        }
+    }
 }
@@ -0,0 +1,76 @@
+//density.rs
+// 非均匀介质的密度场，ConstantMedium 用它缩放消光系数
+use crate::perlin::Perlin;
+use crate::ray::Ray;
+use crate::vec3::Point3;
+
+pub trait DensityField: Send + Sync {
+    /// p 处的密度（≥ 0）
+    fn density(&self, p: &Point3) -> f64;
+    /// 整个场的密度上界，作为跟踪采样的 majorant
+    fn max_density(&self) -> f64;
+
+    /// 从 r.at(t) 起的局部密度上界，以及该上界适用到的参数 t。
+    /// 默认整条光线共用全局上界；体素网格等可以按单元返回更紧的上界
+    fn majorant(&self, _r: &Ray, _t: f64) -> (f64, f64) {
+        (self.max_density(), f64::INFINITY)
+    }
+}
+
+/// 处处相同的密度
+pub struct UniformDensity {
+    pub density: f64,
+}
+
+impl UniformDensity {
+    pub fn new(density: f64) -> Self {
+        Self { density }
+    }
+}
+
+impl DensityField for UniformDensity {
+    fn density(&self, _p: &Point3) -> f64 {
+        self.density
+    }
+
+    fn max_density(&self) -> f64 {
+        self.density
+    }
+}
+
+/// Perlin 湍流密度：turb 截断到 [0, 1] 后乘以 density。
+/// threshold 以下视为空白，其余重新映射到 [0, 1]，用于控制云的覆盖率
+pub struct NoiseDensity {
+    noise: Perlin,
+    pub density: f64,
+    pub scale: f64,
+    pub threshold: f64,
+}
+
+impl NoiseDensity {
+    pub fn new(density: f64, scale: f64) -> Self {
+        Self {
+            noise: Perlin::new(),
+            density,
+            scale,
+            threshold: 0.0,
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold.clamp(0.0, 0.99);
+        self
+    }
+}
+
+impl DensityField for NoiseDensity {
+    fn density(&self, p: &Point3) -> f64 {
+        let t = self.noise.turb(&(self.scale * *p), 7).min(1.0);
+        let t = ((t - self.threshold) / (1.0 - self.threshold)).max(0.0);
+        self.density * t
+    }
+
+    fn max_density(&self) -> f64 {
+        self.density
+    }
+}
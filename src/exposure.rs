@@ -0,0 +1,134 @@
+//exposure.rs
+use crate::rtweekend;
+use std::sync::Arc;
+
+/// 快门开合曲线：描述快门在 [open, close] 区间内的透光权重，
+/// 决定光线时间 Ray::time 的采样分布
+#[derive(Clone, Debug, Default)]
+pub enum ShutterCurve {
+    /// 理想快门，瞬间全开全关（均匀分布）
+    #[default]
+    Box,
+    /// 三角形曲线，中点完全打开
+    Triangle,
+    /// 梯形曲线，ramp 为开启/关闭各占整个区间的比例（0, 0.5]
+    Trapezoid { ramp: f64 },
+    /// 分段常数曲线（由 ShutterCurve::tabulated 构造），存储归一化 CDF
+    Tabulated(Arc<[f64]>),
+}
+
+impl ShutterCurve {
+    /// 用等间隔的权重表构造分段常数快门曲线
+    pub fn tabulated(weights: &[f64]) -> Self {
+        let total: f64 = weights.iter().map(|w| w.max(0.0)).sum();
+        if weights.is_empty() || total <= 0.0 {
+            return ShutterCurve::Box;
+        }
+        let mut cdf = Vec::with_capacity(weights.len() + 1);
+        let mut accum = 0.0;
+        cdf.push(0.0);
+        for w in weights {
+            accum += w.max(0.0) / total;
+            cdf.push(accum);
+        }
+        ShutterCurve::Tabulated(cdf.into())
+    }
+
+    /// 将 [0,1) 上的均匀随机数映射为 [0,1] 上按快门曲线分布的归一化时间
+    pub fn sample(&self, u: f64) -> f64 {
+        match self {
+            ShutterCurve::Box => u,
+            ShutterCurve::Triangle => {
+                if u < 0.5 {
+                    (u / 2.0).sqrt()
+                } else {
+                    1.0 - ((1.0 - u) / 2.0).sqrt()
+                }
+            }
+            ShutterCurve::Trapezoid { ramp } => {
+                let r = ramp.clamp(1e-6, 0.5);
+                let h = 1.0 / (1.0 - r); // 完全打开时的密度
+                let ramp_area = 0.5 * r * h;
+                if u < ramp_area {
+                    (2.0 * u * r / h).sqrt()
+                } else if u < 1.0 - ramp_area {
+                    r + (u - ramp_area) / h
+                } else {
+                    1.0 - (2.0 * (1.0 - u) * r / h).sqrt()
+                }
+            }
+            ShutterCurve::Tabulated(cdf) => {
+                let n = cdf.len() - 1;
+                let idx = cdf.partition_point(|&c| c <= u).clamp(1, n) - 1;
+                let width = cdf[idx + 1] - cdf[idx];
+                let offset = if width > 0.0 {
+                    (u - cdf[idx]) / width
+                } else {
+                    0.0
+                };
+                (idx as f64 + offset) / n as f64
+            }
+        }
+    }
+}
+
+/// 物理相机参数：用焦距、光圈数、快门速度和 ISO 描述镜头与曝光
+#[derive(Clone, Debug)]
+pub struct PhysicalExposure {
+    pub focal_length: f64,          // 焦距（mm）
+    pub f_number: f64,              // 光圈数 N（f/N）
+    pub shutter_speed: f64,         // 曝光时间（秒），只影响亮度
+    pub iso: f64,                   // 感光度
+    pub exposure_compensation: f64, // 曝光补偿（EV）
+    pub sensor_height: f64,         // 传感器高度（mm），默认全画幅 24mm
+    pub units_per_meter: f64,       // 场景单位与米的换算
+}
+
+impl Default for PhysicalExposure {
+    fn default() -> Self {
+        Self {
+            focal_length: 50.0,
+            f_number: 8.0,
+            shutter_speed: 1.0 / 125.0,
+            iso: 100.0,
+            exposure_compensation: 0.0,
+            sensor_height: 24.0,
+            units_per_meter: 1.0,
+        }
+    }
+}
+
+impl PhysicalExposure {
+    pub fn new(focal_length: f64, f_number: f64, shutter_speed: f64, iso: f64) -> Self {
+        Self {
+            focal_length,
+            f_number,
+            shutter_speed,
+            iso,
+            ..Default::default()
+        }
+    }
+
+    /// 由焦距和传感器高度得到的垂直视野角度（degrees）
+    pub fn vfov(&self) -> f64 {
+        let half = (0.5 * self.sensor_height / self.focal_length).atan();
+        2.0 * half * 180.0 / rtweekend::PI
+    }
+
+    /// 光圈（入瞳）半径，单位为场景单位
+    pub fn aperture_radius(&self) -> f64 {
+        let diameter_m = self.focal_length / self.f_number / 1000.0;
+        0.5 * diameter_m * self.units_per_meter
+    }
+
+    /// ISO 100 下的曝光值 EV100
+    pub fn ev100(&self) -> f64 {
+        (self.f_number * self.f_number / self.shutter_speed * 100.0 / self.iso).log2()
+    }
+
+    /// 将场景辐亮度换算到输出像素值的缩放系数（含曝光补偿）
+    pub fn exposure_scale(&self) -> f64 {
+        let max_luminance = 1.2 * (self.ev100() - self.exposure_compensation).exp2();
+        1.0 / max_luminance
+    }
+}
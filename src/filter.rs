@@ -0,0 +1,184 @@
+//filter.rs
+use crate::rtweekend;
+
+/// 像素重建滤波器，radius 以像素为单位
+#[derive(Clone, Debug)]
+pub enum Filter {
+    Box {
+        radius: f64,
+    },
+    Tent {
+        radius: f64,
+    },
+    Gaussian {
+        radius: f64,
+        sigma: f64,
+    },
+    /// Mitchell-Netravali，常用 b = c = 1/3
+    Mitchell {
+        radius: f64,
+        b: f64,
+        c: f64,
+    },
+    /// Lanczos 窗口化 sinc，tau 为 sinc 窗口的周期数
+    Lanczos {
+        radius: f64,
+        tau: f64,
+    },
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        // 半径 0.5 的盒式滤波器与像素内均匀采样后取平均等价
+        Filter::Box { radius: 0.5 }
+    }
+}
+
+impl Filter {
+    pub fn gaussian(radius: f64) -> Self {
+        Filter::Gaussian { radius, sigma: 0.5 }
+    }
+
+    pub fn mitchell(radius: f64) -> Self {
+        Filter::Mitchell {
+            radius,
+            b: 1.0 / 3.0,
+            c: 1.0 / 3.0,
+        }
+    }
+
+    pub fn lanczos(radius: f64) -> Self {
+        Filter::Lanczos { radius, tau: 3.0 }
+    }
+
+    pub fn radius(&self) -> f64 {
+        match self {
+            Filter::Box { radius }
+            | Filter::Tent { radius }
+            | Filter::Gaussian { radius, .. }
+            | Filter::Mitchell { radius, .. }
+            | Filter::Lanczos { radius, .. } => *radius,
+        }
+    }
+
+    /// 滤波器在像素偏移 (x, y) 处的权重（可分离，可能为负）
+    pub fn evaluate(&self, x: f64, y: f64) -> f64 {
+        self.evaluate_1d(x) * self.evaluate_1d(y)
+    }
+
+    fn evaluate_1d(&self, x: f64) -> f64 {
+        let r = self.radius();
+        let x = x.abs();
+        if x > r {
+            return 0.0;
+        }
+        match self {
+            Filter::Box { .. } => 1.0,
+            Filter::Tent { .. } => r - x,
+            Filter::Gaussian { sigma, .. } => {
+                let g = |d: f64| (-d * d / (2.0 * sigma * sigma)).exp();
+                (g(x) - g(r)).max(0.0)
+            }
+            Filter::Mitchell { b, c, .. } => mitchell_1d(2.0 * x / r, *b, *c),
+            Filter::Lanczos { tau, .. } => sinc(x) * sinc(x / tau),
+        }
+    }
+}
+
+fn mitchell_1d(x: f64, b: f64, c: f64) -> f64 {
+    let x = x.abs();
+    if x <= 1.0 {
+        ((12.0 - 9.0 * b - 6.0 * c) * x * x * x
+            + (-18.0 + 12.0 * b + 6.0 * c) * x * x
+            + (6.0 - 2.0 * b))
+            / 6.0
+    } else if x <= 2.0 {
+        ((-b - 6.0 * c) * x * x * x
+            + (6.0 * b + 30.0 * c) * x * x
+            + (-12.0 * b - 48.0 * c) * x
+            + (8.0 * b + 24.0 * c))
+            / 6.0
+    } else {
+        0.0
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-5 {
+        1.0
+    } else {
+        let px = rtweekend::PI * x;
+        px.sin() / px
+    }
+}
+
+/// 样本如何交给重建滤波器
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FilterMode {
+    /// 按 |f| 的分布对像素内偏移做重要性采样，样本只写入所属像素
+    #[default]
+    ImportanceSample,
+    /// 像素内均匀采样，样本按滤波器权重溅射到半径内的所有邻近像素
+    Splat,
+}
+
+/// 对可分离滤波器的一维 |f| 做分段常数表格化，用于重要性采样
+#[derive(Clone, Debug)]
+pub struct FilterSampler {
+    filter: Filter,
+    radius: f64,
+    pdf: Vec<f64>,
+    cdf: Vec<f64>,
+}
+
+impl FilterSampler {
+    const TABLE_SIZE: usize = 64;
+
+    pub fn new(filter: &Filter) -> Self {
+        let radius = filter.radius().max(1e-4);
+        let n = Self::TABLE_SIZE;
+        let dx = 2.0 * radius / n as f64;
+
+        let values: Vec<f64> = (0..n)
+            .map(|i| filter.evaluate_1d(-radius + (i as f64 + 0.5) * dx).abs())
+            .collect();
+        let integral: f64 = values.iter().sum::<f64>() * dx;
+
+        let mut cdf = Vec::with_capacity(n + 1);
+        cdf.push(0.0);
+        let mut accum = 0.0;
+        for v in &values {
+            accum += v * dx / integral;
+            cdf.push(accum);
+        }
+        let pdf = values.iter().map(|v| v / integral).collect();
+
+        Self {
+            filter: filter.clone(),
+            radius,
+            pdf,
+            cdf,
+        }
+    }
+
+    /// 返回像素中心偏移 (dx, dy) 及其样本权重 f / pdf
+    pub fn sample(&self) -> (f64, f64, f64) {
+        let (x, pdf_x) = self.sample_1d(rtweekend::random_double());
+        let (y, pdf_y) = self.sample_1d(rtweekend::random_double());
+        let weight = self.filter.evaluate(x, y) / (pdf_x * pdf_y);
+        (x, y, weight)
+    }
+
+    fn sample_1d(&self, u: f64) -> (f64, f64) {
+        let n = self.pdf.len();
+        let idx = self.cdf.partition_point(|&c| c <= u).clamp(1, n) - 1;
+        let width = self.cdf[idx + 1] - self.cdf[idx];
+        let offset = if width > 0.0 {
+            (u - self.cdf[idx]) / width
+        } else {
+            0.5
+        };
+        let x = -self.radius + (idx as f64 + offset) * 2.0 * self.radius / n as f64;
+        (x, self.pdf[idx])
+    }
+}
@@ -1,29 +1,76 @@
 use crate::aabb;
 use crate::aabb::Aabb;
+use crate::color::Color;
 use crate::interval::Interval;
 use crate::material::{Lambertian, Material};
+use crate::onb::Onb;
 use crate::ray::Ray;
 use crate::rtweekend;
+use crate::texture::Texture;
 use crate::vec3;
 use crate::vec3::{Point3, Vec3};
 use std::sync::Arc;
 // 存储射线命中物体时的记录信息
 #[derive(Clone)]
 pub struct HitRecord {
-    pub p: Point3,    // 命中点坐标
-    pub normal: Vec3, // 命中点法线（单位向量）
-    pub t: f64,       // 射线参数 t
+    pub p: Point3,              // 命中点坐标
+    pub normal: Vec3,           // 着色法线（单位向量），材质散射时使用
+    pub geometric_normal: Vec3, // 几何法线，与 normal 同侧，用于防止扰动法线漏光
+    pub tangent: Vec3,          // 沿 u 增大方向的单位切线（未随入射方向翻转）
+    pub bitangent: Vec3,        // 沿 v 增大方向的单位副切线
+    pub t: f64,                 // 射线参数 t
     pub front_face: bool,
     pub mat: Arc<dyn Material + Send + Sync>,
     pub u: f64,
     pub v: f64,
 }
 
+/// 透明度的使用方式（对应 glTF alphaMode 的 MASK / BLEND）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlphaMode {
+    /// alpha 低于 cutoff 的位置视为镂空
+    Mask { cutoff: f64 },
+    /// 以 alpha 为概率随机命中（随机透明度），多次采样后收敛到半透明效果
+    Blend,
+}
+
+/// 图元上的透明度遮罩：求交时由纹理的 alpha 决定是否跳过该命中
+#[derive(Clone)]
+pub struct AlphaMask {
+    pub texture: Arc<dyn Texture + Send + Sync>,
+    pub mode: AlphaMode,
+}
+
+impl AlphaMask {
+    pub fn new(texture: Arc<dyn Texture + Send + Sync>, mode: AlphaMode) -> Self {
+        Self { texture, mode }
+    }
+
+    /// 命中点是否不透明（应被记录为命中）
+    pub fn is_opaque(&self, u: f64, v: f64, p: &Point3) -> bool {
+        let alpha = self.texture.alpha(u, v, p);
+        match self.mode {
+            AlphaMode::Mask { cutoff } => alpha >= cutoff,
+            AlphaMode::Blend => alpha >= 1.0 || rtweekend::random_double() < alpha,
+        }
+    }
+}
+
 // 可命中物体的统一接口
 pub trait Hittable: Send + Sync {
     // 判断射线是否命中物体，若命中则填充 HitRecord
     fn hit(&self, r: &Ray, ray_t: &Interval, hit_record: &mut HitRecord) -> bool;
     fn bounding_box(&self) -> Aabb;
+
+    // 光线在 ray_t 内穿过彩色介质时的按通道修正权重（见 ConstantMedium），其余物体为 1
+    fn transmittance(&self, _r: &Ray, _ray_t: &Interval) -> Color {
+        Color::new(1.0, 1.0, 1.0)
+    }
+
+    // 是否含有需要 transmittance 修正的彩色介质，用来跳过不含介质的子树
+    fn has_chromatic_media(&self) -> bool {
+        false
+    }
 }
 
 impl HitRecord {
@@ -36,6 +83,31 @@ impl HitRecord {
         } else {
             self.normal = -outward_normal;
         }
+        self.geometric_normal = self.normal;
+    }
+
+    /// 设置表面切线坐标系，退化时由法线任意构造
+    pub fn set_tangents(&mut self, tangent: Vec3, bitangent: Vec3) {
+        if tangent.near_zero() || bitangent.near_zero() {
+            let onb = Onb::new(self.normal);
+            self.tangent = onb.u();
+            self.bitangent = onb.v();
+        } else {
+            self.tangent = vec3::unit_vector(tangent);
+            self.bitangent = vec3::unit_vector(bitangent);
+        }
+    }
+
+    /// 把法线与切线坐标系变换回世界空间：法线用 normal_fn（逆转置），切线用 tangent_fn
+    pub fn transform_frame(
+        &mut self,
+        normal_fn: impl Fn(Vec3) -> Vec3,
+        tangent_fn: impl Fn(Vec3) -> Vec3,
+    ) {
+        self.normal = vec3::unit_vector(normal_fn(self.normal));
+        self.geometric_normal = vec3::unit_vector(normal_fn(self.geometric_normal));
+        self.tangent = vec3::unit_vector(tangent_fn(self.tangent));
+        self.bitangent = vec3::unit_vector(tangent_fn(self.bitangent));
     }
 }
 impl Default for HitRecord {
@@ -43,6 +115,9 @@ impl Default for HitRecord {
         Self {
             p: Point3::default(),
             normal: Vec3::default(),
+            geometric_normal: Vec3::default(),
+            tangent: Vec3::default(),
+            bitangent: Vec3::default(),
             mat: Arc::new(Lambertian::default()), // 或某个默认材质
             t: 0.0,
             front_face: false,
@@ -70,12 +145,16 @@ impl Translate {
     pub fn bounding_box(&self) -> Aabb {
         self.bbox
     }
+
+    // 将光线反向偏移
+    fn to_object(&self, r: &Ray) -> Ray {
+        Ray::new(r.origin() - self.offset, r.direction(), r.time())
+    }
 }
 
 impl Hittable for Translate {
     fn hit(&self, r: &Ray, ray_t: &Interval, rec: &mut HitRecord) -> bool {
-        // 将光线反向偏移
-        let moved_r = Ray::new(r.origin() - self.offset, r.direction(), r.time());
+        let moved_r = self.to_object(r);
 
         if !self.object.hit(&moved_r, ray_t, rec) {
             return false;
@@ -88,6 +167,14 @@ impl Hittable for Translate {
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
+
+    fn transmittance(&self, r: &Ray, ray_t: &Interval) -> Color {
+        self.object.transmittance(&self.to_object(r), ray_t)
+    }
+
+    fn has_chromatic_media(&self) -> bool {
+        self.object.has_chromatic_media()
+    }
 }
 
 pub struct RotateY {
@@ -155,9 +242,9 @@ impl RotateY {
     }
 }
 
-impl Hittable for RotateY {
-    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &mut HitRecord) -> bool {
-        // Ray: 世界空间 -> 物体空间
+impl RotateY {
+    // Ray: 世界空间 -> 物体空间
+    fn to_object(&self, r: &Ray) -> Ray {
         let origin = Point3::new(
             self.cos_theta * r.origin().x - self.sin_theta * r.origin().z,
             r.origin().y,
@@ -170,7 +257,13 @@ impl Hittable for RotateY {
             self.cos_theta * r.direction().z + self.sin_theta * r.direction().x,
         );
 
-        let rotated_r = Ray::new(origin, direction, r.time());
+        Ray::new(origin, direction, r.time())
+    }
+}
+
+impl Hittable for RotateY {
+    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &mut HitRecord) -> bool {
+        let rotated_r = self.to_object(r);
 
         if !self.object.hit(&rotated_r, ray_t, rec) {
             return false;
@@ -183,14 +276,16 @@ impl Hittable for RotateY {
             -self.sin_theta * rec.p.x + self.cos_theta * rec.p.z,
         );
 
-        let normal = Vec3::new(
-            self.cos_theta * rec.normal.x + self.sin_theta * rec.normal.z,
-            rec.normal.y,
-            -self.sin_theta * rec.normal.x + self.cos_theta * rec.normal.z,
-        );
+        let rotate = |n: Vec3| {
+            Vec3::new(
+                self.cos_theta * n.x + self.sin_theta * n.z,
+                n.y,
+                -self.sin_theta * n.x + self.cos_theta * n.z,
+            )
+        };
 
         rec.p = p;
-        rec.normal = normal;
+        rec.transform_frame(rotate, rotate);
 
         true
     }
@@ -198,6 +293,14 @@ impl Hittable for RotateY {
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
+
+    fn transmittance(&self, r: &Ray, ray_t: &Interval) -> Color {
+        self.object.transmittance(&self.to_object(r), ray_t)
+    }
+
+    fn has_chromatic_media(&self) -> bool {
+        self.object.has_chromatic_media()
+    }
 }
 
 pub struct RotateX {
@@ -259,9 +362,9 @@ impl RotateX {
     }
 }
 
-impl Hittable for RotateX {
-    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &mut HitRecord) -> bool {
-        // --- 将光线从世界空间变换到物体空间 (逆向旋转) ---
+impl RotateX {
+    // --- 将光线从世界空间变换到物体空间 (逆向旋转) ---
+    fn to_object(&self, r: &Ray) -> Ray {
         let mut origin = r.origin();
         let mut direction = r.direction();
 
@@ -271,7 +374,13 @@ impl Hittable for RotateX {
         direction.y = self.cos_theta * r.direction().y + self.sin_theta * r.direction().z;
         direction.z = -self.sin_theta * r.direction().y + self.cos_theta * r.direction().z;
 
-        let rotated_r = Ray::new(origin, direction, r.time());
+        Ray::new(origin, direction, r.time())
+    }
+}
+
+impl Hittable for RotateX {
+    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &mut HitRecord) -> bool {
+        let rotated_r = self.to_object(r);
 
         // 如果旋转后的光线没有击中物体，就直接返回
         if !self.object.hit(&rotated_r, ray_t, rec) {
@@ -280,16 +389,21 @@ impl Hittable for RotateX {
 
         // --- 将命中点和法线从物体空间变换回世界空间 (正向旋转) ---
         let mut p = rec.p;
-        let mut normal = rec.normal;
 
         p.y = self.cos_theta * rec.p.y - self.sin_theta * rec.p.z;
         p.z = self.sin_theta * rec.p.y + self.cos_theta * rec.p.z;
 
-        normal.y = self.cos_theta * rec.normal.y - self.sin_theta * rec.normal.z;
-        normal.z = self.sin_theta * rec.normal.y + self.cos_theta * rec.normal.z;
+        // 旋转保持点积不变，物体空间中的 front_face 仍然有效
+        let rotate = |n: Vec3| {
+            Vec3::new(
+                n.x,
+                self.cos_theta * n.y - self.sin_theta * n.z,
+                self.sin_theta * n.y + self.cos_theta * n.z,
+            )
+        };
 
         rec.p = p;
-        rec.set_face_normal(&rotated_r, normal);
+        rec.transform_frame(rotate, rotate);
 
         true
     }
@@ -297,6 +411,14 @@ impl Hittable for RotateX {
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
+
+    fn transmittance(&self, r: &Ray, ray_t: &Interval) -> Color {
+        self.object.transmittance(&self.to_object(r), ray_t)
+    }
+
+    fn has_chromatic_media(&self) -> bool {
+        self.object.has_chromatic_media()
+    }
 }
 
 pub struct Scale {
@@ -321,11 +443,17 @@ impl Scale {
     }
 }
 
-impl Hittable for Scale {
-    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &mut HitRecord) -> bool {
+impl Scale {
+    fn to_object(&self, r: &Ray) -> Ray {
         let origin = r.origin() * self.inv_scale;
         let direction = r.direction() * self.inv_scale;
-        let scaled_r = Ray::new(origin, direction, r.time());
+        Ray::new(origin, direction, r.time())
+    }
+}
+
+impl Hittable for Scale {
+    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &mut HitRecord) -> bool {
+        let scaled_r = self.to_object(r);
 
         if !self.object.hit(&scaled_r, ray_t, rec) {
             return false;
@@ -333,8 +461,8 @@ impl Hittable for Scale {
 
         rec.p = rec.p * self.scale;
 
-        let normal = rec.normal * self.inv_scale;
-        rec.set_face_normal(&scaled_r, normal.unit_vector());
+        // 法线按逆转置（1/scale）变换，切线按 scale 变换
+        rec.transform_frame(|n| n * self.inv_scale, |t| t * self.scale);
 
         true
     }
@@ -342,6 +470,14 @@ impl Hittable for Scale {
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
+
+    fn transmittance(&self, r: &Ray, ray_t: &Interval) -> Color {
+        self.object.transmittance(&self.to_object(r), ray_t)
+    }
+
+    fn has_chromatic_media(&self) -> bool {
+        self.object.has_chromatic_media()
+    }
 }
 
 pub struct RotateZ {
@@ -401,8 +537,8 @@ impl RotateZ {
     }
 }
 
-impl Hittable for RotateZ {
-    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &mut HitRecord) -> bool {
+impl RotateZ {
+    fn to_object(&self, r: &Ray) -> Ray {
         let mut origin = r.origin();
         let mut direction = r.direction();
 
@@ -412,23 +548,33 @@ impl Hittable for RotateZ {
         direction.x = self.cos_theta * r.direction().x + self.sin_theta * r.direction().y;
         direction.y = -self.sin_theta * r.direction().x + self.cos_theta * r.direction().y;
 
-        let rotated_r = Ray::new(origin, direction, r.time());
+        Ray::new(origin, direction, r.time())
+    }
+}
+
+impl Hittable for RotateZ {
+    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &mut HitRecord) -> bool {
+        let rotated_r = self.to_object(r);
 
         if !self.object.hit(&rotated_r, ray_t, rec) {
             return false;
         }
 
         let mut p = rec.p;
-        let mut normal = rec.normal;
 
         p.x = self.cos_theta * rec.p.x - self.sin_theta * rec.p.y;
         p.y = self.sin_theta * rec.p.x + self.cos_theta * rec.p.y;
 
-        normal.x = self.cos_theta * rec.normal.x - self.sin_theta * rec.normal.y;
-        normal.y = self.sin_theta * rec.normal.x + self.cos_theta * rec.normal.y;
+        let rotate = |n: Vec3| {
+            Vec3::new(
+                self.cos_theta * n.x - self.sin_theta * n.y,
+                self.sin_theta * n.x + self.cos_theta * n.y,
+                n.z,
+            )
+        };
 
         rec.p = p;
-        rec.set_face_normal(&rotated_r, normal);
+        rec.transform_frame(rotate, rotate);
 
         true
     }
@@ -436,4 +582,12 @@ impl Hittable for RotateZ {
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
+
+    fn transmittance(&self, r: &Ray, ray_t: &Interval) -> Color {
+        self.object.transmittance(&self.to_object(r), ray_t)
+    }
+
+    fn has_chromatic_media(&self) -> bool {
+        self.object.has_chromatic_media()
+    }
 }
@@ -1,5 +1,6 @@
 //hittable_list.rs
 use crate::aabb::Aabb;
+use crate::color::Color;
 use crate::hittable::{HitRecord, Hittable};
 use crate::interval::Interval;
 use crate::ray::Ray;
@@ -49,4 +50,19 @@ impl Hittable for HittableList {
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
+
+    fn transmittance(&self, r: &Ray, ray_t: &Interval) -> Color {
+        self.objects
+            .iter()
+            .filter(|object| object.has_chromatic_media())
+            .fold(Color::new(1.0, 1.0, 1.0), |acc, object| {
+                acc * object.transmittance(r, ray_t)
+            })
+    }
+
+    fn has_chromatic_media(&self) -> bool {
+        self.objects
+            .iter()
+            .any(|object| object.has_chromatic_media())
+    }
 }
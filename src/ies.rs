@@ -0,0 +1,261 @@
+//ies.rs
+// IES LM-63 光度文件（LM-63-1986/1995/2002）：灯具在各方向上的发光强度分布（cd）。
+// 只支持建筑照明最常用的 C 型配光：垂直角 0° 指向灯具正下方（光轴），180° 指向正上方；
+// 水平角绕光轴从 0° 平面逆时针（从上往下看）计。
+// 水平角只给出 0°、0–90°、0–180° 或 90–270° 时分别按旋转对称、四象限对称、左右对称处理
+use crate::rtweekend::PI;
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+
+pub struct IesProfile {
+    vertical: Vec<f64>,     // 垂直角（degrees），递增
+    horizontal: Vec<f64>,   // 水平角（degrees），递增
+    candela: Vec<Vec<f64>>, // candela[h][v]，已乘以倍率和镇流器系数
+    max_candela: f64,
+}
+
+impl IesProfile {
+    pub fn load(path: &str) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|e| Error::new(ErrorKind::InvalidData, format!("{path}: {e}")))
+    }
+
+    /// 解析 IES 文件内容，TILT 数据会被跳过
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let invalid = |msg: String| Error::new(ErrorKind::InvalidData, msg);
+
+        // TILT= 之前是 IESNA 标识和 [KEYWORD] 说明行，之后全部是数字
+        let mut lines = text.lines();
+        let tilt = lines
+            .by_ref()
+            .map(str::trim)
+            .find(|line| line.starts_with("TILT="))
+            .ok_or_else(|| invalid("missing TILT= line".into()))?;
+        let mut numbers = Vec::new();
+        for token in lines.flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ',')) {
+            if token.is_empty() {
+                continue;
+            }
+            let value = token
+                .parse::<f64>()
+                .map_err(|_| invalid(format!("bad number {token:?}")))?;
+            numbers.push(value);
+        }
+        let numbers = &mut numbers.into_iter();
+
+        if tilt == "TILT=INCLUDE" {
+            // 灯具几何类型、角度数 n、n 个角度、n 个系数
+            next(numbers, "tilt geometry")?;
+            let count = next_count(numbers, "tilt count")?;
+            for _ in 0..2 * count {
+                next(numbers, "tilt data")?;
+            }
+        }
+
+        let _lamps = next(numbers, "lamp count")?;
+        let _lumens = next(numbers, "lumens per lamp")?;
+        let multiplier = next(numbers, "candela multiplier")?;
+        let n_vertical = next_count(numbers, "vertical angle count")?;
+        let n_horizontal = next_count(numbers, "horizontal angle count")?;
+        let photometric_type = next(numbers, "photometric type")?;
+        for what in ["units", "width", "length", "height"] {
+            next(numbers, what)?;
+        }
+        let ballast = next(numbers, "ballast factor")?;
+        for what in ["file generation type", "input watts"] {
+            next(numbers, what)?;
+        }
+        if photometric_type != 1.0 {
+            return Err(invalid(format!(
+                "photometric type {photometric_type} is not supported (only type C)"
+            )));
+        }
+        if n_vertical == 0 || n_horizontal == 0 {
+            return Err(invalid("empty angle table".into()));
+        }
+        // 两组角度加上 n_vertical × n_horizontal 个发光强度，分配之前先确认文件中有这么多数
+        let table = n_vertical
+            .checked_mul(n_horizontal)
+            .and_then(|n| n.checked_add(n_vertical + n_horizontal));
+        if table.is_none_or(|n| n > numbers.len()) {
+            return Err(invalid(format!(
+                "{n_vertical} × {n_horizontal} angle table exceeds the file"
+            )));
+        }
+
+        let vertical = numbers.take(n_vertical).collect::<Vec<_>>();
+        let horizontal = numbers.take(n_horizontal).collect::<Vec<_>>();
+        let scale = multiplier * if ballast > 0.0 { ballast } else { 1.0 };
+        let candela = (0..n_horizontal)
+            .map(|_| numbers.take(n_vertical).map(|c| c * scale).collect())
+            .collect::<Vec<Vec<_>>>();
+        let max_candela = candela.iter().flatten().fold(0.0_f64, |m, &c| m.max(c));
+
+        Ok(Self {
+            vertical,
+            horizontal,
+            candela,
+            max_candela,
+        })
+    }
+
+    pub fn max_candela(&self) -> f64 {
+        self.max_candela
+    }
+
+    /// 垂直角 vertical、水平角 horizontal（degrees）方向上的发光强度（cd），双线性插值
+    pub fn candela(&self, vertical: f64, horizontal: f64) -> f64 {
+        let first = self.vertical[0];
+        let last = self.vertical[self.vertical.len() - 1];
+        if vertical < first || vertical > last {
+            return 0.0;
+        }
+        let (v0, v1, tv) = bracket(&self.vertical, vertical);
+        let (h0, h1, th) = self.bracket_horizontal(horizontal);
+        let lerp = |row: &Vec<f64>| row[v0] + tv * (row[v1] - row[v0]);
+        let a = lerp(&self.candela[h0]);
+        let b = lerp(&self.candela[h1]);
+        a + th * (b - a)
+    }
+
+    /// 在整个球面上积分得到的总光通量（lm）
+    pub fn flux(&self) -> f64 {
+        const STEPS_V: usize = 180;
+        const STEPS_H: usize = 180;
+        let dv = PI / STEPS_V as f64;
+        let dh = 2.0 * PI / STEPS_H as f64;
+        let mut flux = 0.0;
+        for i in 0..STEPS_V {
+            let theta = (i as f64 + 0.5) * dv;
+            let mut ring = 0.0;
+            for j in 0..STEPS_H {
+                let phi = (j as f64 + 0.5) * dh;
+                ring += self.candela(theta.to_degrees(), phi.to_degrees());
+            }
+            flux += ring * theta.sin() * dv * dh;
+        }
+        flux
+    }
+
+    // 按对称性把水平角折回表中覆盖的范围，返回插值的两个下标和比例
+    fn bracket_horizontal(&self, horizontal: f64) -> (usize, usize, f64) {
+        let n = self.horizontal.len();
+        if n == 1 {
+            return (0, 0, 0.0);
+        }
+        let first = self.horizontal[0];
+        let last = self.horizontal[n - 1];
+        let mut phi = horizontal.rem_euclid(360.0);
+        if first == 0.0 && last == 90.0 {
+            // 四象限对称
+            if phi > 180.0 {
+                phi = 360.0 - phi;
+            }
+            if phi > 90.0 {
+                phi = 180.0 - phi;
+            }
+        } else if first == 0.0 && last == 180.0 {
+            // 关于 0–180° 平面左右对称
+            if phi > 180.0 {
+                phi = 360.0 - phi;
+            }
+        } else if first == 90.0 && last == 270.0 {
+            // 关于 90–270° 平面左右对称
+            if phi < 90.0 {
+                phi = 180.0 - phi;
+            } else if phi > 270.0 {
+                phi = 540.0 - phi;
+            }
+        } else if phi > last {
+            // 完整的 0–360°：最后一个角度到 360° 之间与第一个角度首尾相接
+            let span = first + 360.0 - last;
+            let t = if span > 0.0 { (phi - last) / span } else { 0.0 };
+            return (n - 1, 0, t);
+        } else if phi < first {
+            let span = first + 360.0 - last;
+            let t = if span > 0.0 {
+                (phi + 360.0 - last) / span
+            } else {
+                0.0
+            };
+            return (n - 1, 0, t);
+        }
+        bracket(&self.horizontal, phi)
+    }
+}
+
+fn next(numbers: &mut std::vec::IntoIter<f64>, what: &str) -> io::Result<f64> {
+    numbers.next().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("unexpected end of file reading {what}"),
+        )
+    })
+}
+
+// 个数必须是非负整数，且不超过剩余的数字个数（每一项至少占一个数）
+fn next_count(numbers: &mut std::vec::IntoIter<f64>, what: &str) -> io::Result<usize> {
+    let value = next(numbers, what)?;
+    if !(value >= 0.0 && value.fract() == 0.0 && value <= numbers.len() as f64) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("bad {what} {value}"),
+        ));
+    }
+    Ok(value as usize)
+}
+
+// 在递增的 angles 中找到包含 x 的区间，越界时取端点
+fn bracket(angles: &[f64], x: f64) -> (usize, usize, f64) {
+    let n = angles.len();
+    let i = angles.partition_point(|&a| a <= x);
+    if i == 0 {
+        return (0, 0, 0.0);
+    }
+    if i >= n {
+        return (n - 1, n - 1, 0.0);
+    }
+    let (a, b) = (angles[i - 1], angles[i]);
+    let t = if b > a { (x - a) / (b - a) } else { 0.0 };
+    (i - 1, i, t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1 盏灯、倍率 1、2 个垂直角 × 1 个水平角、C 型、米、尺寸 0、镇流器 1、版本 1、功率 10
+    fn header(n_vertical: &str, n_horizontal: &str) -> String {
+        format!(
+            "IESNA:LM-63-2002\nTILT=NONE\n1 1000 1 {n_vertical} {n_horizontal} 1 2 0 0 0\n1 1 10\n"
+        )
+    }
+
+    #[test]
+    fn parses_minimal_profile() {
+        let text = header("2", "1") + "0 90\n0\n100 50\n";
+        let profile = IesProfile::parse(&text).unwrap();
+        assert_eq!(profile.max_candela(), 100.0);
+        assert_eq!(profile.candela(45.0, 0.0), 75.0);
+    }
+
+    #[test]
+    fn rejects_bad_counts() {
+        for (v, h) in [
+            ("-1", "1"),
+            ("2.5", "1"),
+            ("NaN", "1"),
+            ("inf", "1"),
+            ("1e18", "1"),
+        ] {
+            let text = header(v, h) + "0 90\n0\n100 50\n";
+            let err = IesProfile::parse(&text).err().expect(v);
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+        }
+        // 个数本身合理，但表格超出文件中剩余的数字
+        let text = header("2", "3") + "0 90\n0\n100 50\n";
+        assert!(IesProfile::parse(&text).is_err());
+        let text = "TILT=INCLUDE\n1 1e300\n".to_string();
+        assert!(IesProfile::parse(&text).is_err());
+    }
+}
@@ -0,0 +1,586 @@
+//light.rs
+// 可直接采样的解析光源，放进 camera.lights 后在每个漫反射/体散射点用阴影光线计算直接光照。
+// 点光源、聚光灯、IES 灯具、投影灯、平行光没有几何体，只通过光源采样照亮场景；
+// 面光源同时是可求交的几何体，需要把同一个 Arc 既加入 world 又加入 camera.lights：
+// 相机或镜面路径看到它时计入自发光，做过光源采样的路径再命中时不重复计入。
+// 只加入 world 的面光源没有被采样，任何路径命中时都计入自发光
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::color::Color;
+use crate::hittable::{HitRecord, Hittable};
+use crate::ies::IesProfile;
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::rtweekend::{self, INFINITY, PI};
+use crate::texture::Texture;
+use crate::vec3::{self, Point3, Vec3};
+
+/// 一次光源采样的结果
+pub struct LightSample {
+    pub direction: Vec3, // 从着色点指向光源的单位向量
+    pub distance: f64,   // 到光源上采样点的距离，平行光为 INFINITY
+    pub radiance: Color, // 入射辐亮度 / pdf（点光源等 delta 光源即照度）
+}
+
+pub trait Light: Send + Sync {
+    /// 从 p 采样一个到达 p 的光照，照不到 p 时返回 None
+    fn sample(&self, p: &Point3) -> Option<LightSample>;
+
+    /// 命中材质 mat 是否是这个光源自身的发光面（面光源），用于避免光源采样与 BSDF 采样重复计入
+    fn emits(&self, _mat: &Arc<dyn Material + Send + Sync>) -> bool {
+        false
+    }
+}
+
+// 发光强度 I 的光源在 p 处的采样：照度 I / d²
+fn inverse_square(position: Point3, intensity: Color, p: &Point3) -> Option<LightSample> {
+    let d = position - *p;
+    let distance = d.length();
+    if distance <= 0.0 {
+        return None;
+    }
+    Some(LightSample {
+        direction: d / distance,
+        distance,
+        radiance: intensity / (distance * distance),
+    })
+}
+
+/// 各向均匀发光的点光源
+pub struct PointLight {
+    pub position: Point3,
+    pub intensity: Color, // 发光强度（cd）
+}
+
+impl PointLight {
+    /// intensity 为发光强度（cd），乘以 color
+    pub fn new(position: Point3, color: Color, intensity: f64) -> Self {
+        Self {
+            position,
+            intensity: intensity * color,
+        }
+    }
+
+    /// 按总光通量（lm）给出亮度：I = Φ / 4π
+    pub fn from_power(position: Point3, color: Color, power: f64) -> Self {
+        Self::new(position, color, power / (4.0 * PI))
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        inverse_square(self.position, self.intensity, p)
+    }
+}
+
+/// 聚光灯：inner 角内为全亮度，到 outer 角平滑衰减为 0
+pub struct SpotLight {
+    pub position: Point3,
+    pub direction: Vec3,  // 照射方向（单位向量）
+    pub intensity: Color, // 光轴上的发光强度（cd）
+    cos_inner: f64,
+    cos_outer: f64,
+}
+
+impl SpotLight {
+    /// 半角 inner_angle / outer_angle 以度计
+    pub fn new(
+        position: Point3,
+        direction: Vec3,
+        color: Color,
+        intensity: f64,
+        inner_angle: f64,
+        outer_angle: f64,
+    ) -> Self {
+        let outer = rtweekend::degrees_to_radians(outer_angle.clamp(0.0, 90.0));
+        let inner = rtweekend::degrees_to_radians(inner_angle).clamp(0.0, outer);
+        Self {
+            position,
+            direction: vec3::unit_vector(direction),
+            intensity: intensity * color,
+            cos_inner: inner.cos(),
+            cos_outer: outer.cos(),
+        }
+    }
+
+    /// 按总光通量给出亮度，衰减区按一半计入：I = Φ / (2π (1 - (cos inner + cos outer) / 2))
+    pub fn from_power(
+        position: Point3,
+        direction: Vec3,
+        color: Color,
+        power: f64,
+        inner_angle: f64,
+        outer_angle: f64,
+    ) -> Self {
+        let mut light = Self::new(position, direction, color, 1.0, inner_angle, outer_angle);
+        let solid_angle = 2.0 * PI * (1.0 - 0.5 * (light.cos_inner + light.cos_outer));
+        light.intensity = power / solid_angle.max(1e-9) * color;
+        light
+    }
+
+    // 光轴夹角的余弦为 cos_theta 时的相对强度
+    fn falloff(&self, cos_theta: f64) -> f64 {
+        if cos_theta >= self.cos_inner {
+            return 1.0;
+        }
+        if cos_theta <= self.cos_outer {
+            return 0.0;
+        }
+        let t = (cos_theta - self.cos_outer) / (self.cos_inner - self.cos_outer);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+impl Light for SpotLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        let mut sample = inverse_square(self.position, self.intensity, p)?;
+        let falloff = self.falloff(vec3::dot(-sample.direction, self.direction));
+        if falloff <= 0.0 {
+            return None;
+        }
+        sample.radiance = falloff * sample.radiance;
+        Some(sample)
+    }
+}
+
+/// 配光来自 IES 文件的点光源：筒灯、洗墙灯、路灯等。
+/// direction 为灯具光轴（配光的垂直角 0°，筒灯朝下），reference 为水平角 0° 的方向
+pub struct IesLight {
+    pub position: Point3,
+    pub profile: Arc<IesProfile>,
+    pub color: Color, // 乘在配光强度上，默认白色；with_power 会改变它的大小
+    axis: Vec3,
+    reference: Vec3,
+    side: Vec3, // 水平角 90° 的方向
+}
+
+impl IesLight {
+    pub fn new(position: Point3, direction: Vec3, profile: Arc<IesProfile>) -> Self {
+        let mut light = Self {
+            position,
+            profile,
+            color: Color::new(1.0, 1.0, 1.0),
+            axis: vec3::unit_vector(direction),
+            reference: Vec3::default(),
+            side: Vec3::default(),
+        };
+        // 默认取世界 x 轴在垂直于光轴的平面上的投影作为水平角 0°
+        let x = Vec3::new(1.0, 0.0, 0.0);
+        let reference = if vec3::dot(x, light.axis).abs() > 0.999 {
+            Vec3::new(0.0, 0.0, 1.0)
+        } else {
+            x
+        };
+        light.set_reference(reference);
+        light
+    }
+
+    /// 水平角 0° 对应的方向，用于旋转非对称配光（例如让洗墙灯对准墙面）
+    pub fn with_reference(mut self, reference: Vec3) -> Self {
+        self.set_reference(reference);
+        self
+    }
+
+    /// 光色，文件中的强度乘以 color
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// 把总光通量缩放到 power（lm），保持配光形状
+    pub fn with_power(mut self, power: f64) -> Self {
+        let flux = self.profile.flux();
+        if flux > 0.0 {
+            self.color = power / flux * self.color;
+        }
+        self
+    }
+
+    fn set_reference(&mut self, reference: Vec3) {
+        let r = reference - vec3::dot(reference, self.axis) * self.axis;
+        self.reference = vec3::unit_vector(r);
+        // 从光轴反方向（灯具上方）往下看时逆时针旋转 90°
+        self.side = vec3::cross(self.reference, self.axis);
+    }
+}
+
+impl Light for IesLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        let d = vec3::unit_vector(*p - self.position);
+        let vertical = vec3::dot(d, self.axis).clamp(-1.0, 1.0).acos().to_degrees();
+        let horizontal = vec3::dot(d, self.side)
+            .atan2(vec3::dot(d, self.reference))
+            .to_degrees();
+        let candela = self.profile.candela(vertical, horizontal);
+        if candela <= 0.0 {
+            return None;
+        }
+        inverse_square(self.position, candela * self.color, p)
+    }
+}
+
+/// 投影灯：把纹理（gobo 图案、幻灯片）按透视投影到场景中，图案外不发光。
+/// 纹理值为 1 的方向发光强度为 intensity（cd）
+pub struct ProjectorLight {
+    pub position: Point3,
+    pub texture: Arc<dyn Texture>,
+    pub intensity: Color,
+    forward: Vec3,
+    up: Vec3,
+    right: Vec3,
+    vfov: f64,
+    aspect_ratio: f64,
+}
+
+impl ProjectorLight {
+    /// vfov 为竖直方向的视场角（degrees），默认正方形画幅、y 轴朝上
+    pub fn new(
+        position: Point3,
+        direction: Vec3,
+        texture: Arc<dyn Texture>,
+        intensity: f64,
+        vfov: f64,
+    ) -> Self {
+        let mut light = Self {
+            position,
+            texture,
+            intensity: intensity * Color::new(1.0, 1.0, 1.0),
+            forward: vec3::unit_vector(direction),
+            up: Vec3::default(),
+            right: Vec3::default(),
+            vfov,
+            aspect_ratio: 1.0,
+        };
+        light.set_up(Vec3::new(0.0, 1.0, 0.0));
+        light
+    }
+
+    /// 画面的上方向，与相机的 vup 相同
+    pub fn with_up(mut self, vup: Vec3) -> Self {
+        self.set_up(vup);
+        self
+    }
+
+    /// 画幅宽高比，通常与纹理图像一致
+    pub fn with_aspect_ratio(mut self, aspect_ratio: f64) -> Self {
+        self.aspect_ratio = aspect_ratio;
+        self
+    }
+
+    fn set_up(&mut self, vup: Vec3) {
+        // 与相机相同的右手系：right = forward × vup
+        let vup = if vec3::cross(vup, self.forward).near_zero() {
+            Vec3::new(0.0, 0.0, 1.0)
+        } else {
+            vup
+        };
+        self.right = vec3::unit_vector(vec3::cross(self.forward, vup));
+        self.up = vec3::cross(self.right, self.forward);
+    }
+}
+
+impl Light for ProjectorLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        let d = *p - self.position;
+        let z = vec3::dot(d, self.forward);
+        if z <= 0.0 {
+            return None;
+        }
+        // 投影到距离 1 的成像平面上，再映射到纹理坐标（v 朝上）
+        let half_height = rtweekend::degrees_to_radians(self.vfov / 2.0).tan();
+        let half_width = self.aspect_ratio * half_height;
+        let u = 0.5 + 0.5 * vec3::dot(d, self.right) / (z * half_width);
+        let v = 0.5 + 0.5 * vec3::dot(d, self.up) / (z * half_height);
+        if !(0.0..=1.0).contains(&u) || !(0.0..=1.0).contains(&v) {
+            return None;
+        }
+        let pattern = self.texture.value(u, v, &self.position);
+        if pattern.near_zero() {
+            return None;
+        }
+        inverse_square(self.position, pattern * self.intensity, p)
+    }
+}
+
+/// 无穷远处的平行光（太阳、月亮）；角直径不为 0 时在圆锥内采样，产生软阴影
+pub struct DirectionalLight {
+    pub direction: Vec3,    // 指向光源的单位向量
+    pub illuminance: Color, // 垂直入射时的照度（lux）
+    cos_max: f64,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Vec3, color: Color, illuminance: f64) -> Self {
+        Self {
+            direction: vec3::unit_vector(direction),
+            illuminance: illuminance * color,
+            cos_max: 1.0,
+        }
+    }
+
+    /// 光源的角直径（度），太阳约为 0.53°
+    pub fn with_angular_diameter(mut self, degrees: f64) -> Self {
+        self.cos_max = rtweekend::degrees_to_radians(0.5 * degrees).cos();
+        self
+    }
+}
+
+impl Light for DirectionalLight {
+    fn sample(&self, _p: &Point3) -> Option<LightSample> {
+        // 圆锥内辐亮度均匀为 E / Ω，均匀采样的 pdf 为 1 / Ω，比值恰为照度
+        let direction = if self.cos_max < 1.0 {
+            let cos_theta = 1.0 - rtweekend::random_double() * (1.0 - self.cos_max);
+            let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+            let phi = 2.0 * PI * rtweekend::random_double();
+            let local = Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+            Onb::new(self.direction).transform(local)
+        } else {
+            self.direction
+        };
+        Some(LightSample {
+            direction,
+            distance: INFINITY,
+            radiance: self.illuminance,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Shape {
+    Quad { q: Point3, u: Vec3, v: Vec3 },
+    Disk { center: Point3, u: Vec3, v: Vec3 }, // u、v 为互相垂直、长度为半径的向量
+}
+
+/// 平行四边形或圆盘面光源，默认只有法线一侧发光
+pub struct AreaLight {
+    shape: Shape,
+    normal: Vec3,
+    area: f64,
+    color: Color,
+    power: Option<f64>,
+    two_sided: bool,
+    radiance: Color,
+    emitter: Arc<dyn Material + Send + Sync>,
+}
+
+impl AreaLight {
+    /// 以 q 为角点、u、v 为边的平行四边形（u、v 不必垂直），发光一侧的法线为 u × v，
+    /// radiance 为辐亮度（cd/m²）
+    pub fn quad(q: Point3, u: Vec3, v: Vec3, radiance: Color) -> Self {
+        let n = vec3::cross(u, v);
+        Self::build(Shape::Quad { q, u, v }, n, n.length(), radiance)
+    }
+
+    /// 圆心 center、法线 normal（发光一侧）、半径 radius 的圆盘
+    pub fn disk(center: Point3, normal: Vec3, radius: f64, radiance: Color) -> Self {
+        let onb = Onb::new(vec3::unit_vector(normal));
+        let (u, v) = (radius * onb.u(), radius * onb.v());
+        Self::build(
+            Shape::Disk { center, u, v },
+            normal,
+            PI * radius * radius,
+            radiance,
+        )
+    }
+
+    /// 两面都发光
+    pub fn with_two_sided(mut self, two_sided: bool) -> Self {
+        self.two_sided = two_sided;
+        self.update();
+        self
+    }
+
+    /// 按总光通量（lm）给出亮度，此时构造时的 radiance 只作为光色：
+    /// 朗伯发光面 L = Φ / (π A)，两面发光时每面各占一半
+    pub fn with_power(mut self, power: f64) -> Self {
+        self.power = Some(power);
+        self.update();
+        self
+    }
+
+    /// 发光面的辐亮度
+    pub fn radiance(&self) -> Color {
+        self.radiance
+    }
+
+    fn build(shape: Shape, normal: Vec3, area: f64, color: Color) -> Self {
+        let mut light = Self {
+            shape,
+            normal: vec3::unit_vector(normal),
+            area,
+            color,
+            power: None,
+            two_sided: false,
+            radiance: Color::default(),
+            emitter: Arc::new(LightEmitter {
+                radiance: Color::default(),
+                two_sided: false,
+            }),
+        };
+        light.update();
+        light
+    }
+
+    fn update(&mut self) {
+        let sides = if self.two_sided { 2.0 } else { 1.0 };
+        self.radiance = match self.power {
+            Some(power) => power / (PI * self.area * sides) * self.color,
+            None => self.color,
+        };
+        self.emitter = Arc::new(LightEmitter {
+            radiance: self.radiance,
+            two_sided: self.two_sided,
+        });
+    }
+
+    // 形状上均匀分布的点
+    fn sample_point(&self) -> Point3 {
+        match self.shape {
+            Shape::Quad { q, u, v } => {
+                q + rtweekend::random_double() * u + rtweekend::random_double() * v
+            }
+            Shape::Disk { center, u, v } => {
+                let r = rtweekend::random_double().sqrt();
+                let phi = 2.0 * PI * rtweekend::random_double();
+                center + r * phi.cos() * u + r * phi.sin() * v
+            }
+        }
+    }
+}
+
+impl Light for AreaLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        let d = self.sample_point() - *p;
+        let distance = d.length();
+        if distance <= 0.0 {
+            return None;
+        }
+        let direction = d / distance;
+        let cos_light = vec3::dot(self.normal, -direction);
+        if cos_light <= 0.0 && !self.two_sided {
+            return None;
+        }
+        // 面积采样换算到立体角：pdf = d² / (A · |cosθ'|)
+        Some(LightSample {
+            direction,
+            distance,
+            radiance: self.radiance * (self.area * cos_light.abs() / (distance * distance)),
+        })
+    }
+
+    fn emits(&self, mat: &Arc<dyn Material + Send + Sync>) -> bool {
+        Arc::ptr_eq(&self.emitter, mat)
+    }
+}
+
+impl Hittable for AreaLight {
+    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &mut HitRecord) -> bool {
+        let denom = vec3::dot(self.normal, r.direction());
+        if denom.abs() < 1e-8 {
+            return false;
+        }
+        let (origin, u, v) = match self.shape {
+            Shape::Quad { q, u, v } => (q, u, v),
+            Shape::Disk { center, u, v } => (center, u, v),
+        };
+        let t = vec3::dot(self.normal, origin - r.origin()) / denom;
+        if !ray_t.contains(t) {
+            return false;
+        }
+
+        // 与 Quad 相同的平面坐标，u、v 不必垂直
+        let p = r.at(t);
+        let n = vec3::cross(u, v);
+        let w = n / vec3::dot(n, n);
+        let planar = p - origin;
+        let a = vec3::dot(w, vec3::cross(planar, v));
+        let b = vec3::dot(w, vec3::cross(u, planar));
+        let inside = match self.shape {
+            Shape::Quad { .. } => (0.0..=1.0).contains(&a) && (0.0..=1.0).contains(&b),
+            Shape::Disk { .. } => a * a + b * b <= 1.0,
+        };
+        if !inside {
+            return false;
+        }
+
+        rec.t = t;
+        rec.p = p;
+        rec.u = a;
+        rec.v = b;
+        rec.set_face_normal(r, self.normal);
+        rec.set_tangents(u, v);
+        rec.mat = self.emitter.clone();
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        match self.shape {
+            Shape::Quad { q, u, v } => Aabb::new_boxes(
+                &Aabb::new_points(q, q + u + v),
+                &Aabb::new_points(q + u, q + v),
+            )
+            .pad(),
+            Shape::Disk { center, u, v } => {
+                let extent = Vec3::new(u.x.hypot(v.x), u.y.hypot(v.y), u.z.hypot(v.z));
+                Aabb::new_points(center - extent, center + extent).pad()
+            }
+        }
+    }
+}
+
+// 面光源表面：只发光不散射
+struct LightEmitter {
+    radiance: Color,
+    two_sided: bool,
+}
+
+impl Material for LightEmitter {
+    fn scatter(
+        &self,
+        _r_in: &Ray,
+        _rec: &HitRecord,
+        _attenuation: &mut Color,
+        _scattered: &mut Ray,
+    ) -> bool {
+        false
+    }
+
+    fn emitted(&self, _r_in: &Ray, rec: &HitRecord) -> Color {
+        if rec.front_face || self.two_sided {
+            self.radiance
+        } else {
+            Color::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 斜边的面光源：阴影光线采样到的点都必须能被求交命中，命中区域之外的点则不能
+    #[test]
+    fn skewed_quad_hits_match_samples() {
+        let light = AreaLight::quad(
+            Point3::new(0.0, 1.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, -1.0),
+            Color::new(1.0, 1.0, 1.0),
+        );
+        let origin = Point3::new(0.5, 2.0, -0.3);
+        let ray_t = Interval::new(0.001, INFINITY);
+        for _ in 0..1000 {
+            let sample = light.sample(&origin).unwrap();
+            let r = Ray::new(origin, sample.direction, 0.0);
+            let mut rec = HitRecord::default();
+            assert!(light.hit(&r, &ray_t, &mut rec));
+            assert!((rec.t - sample.distance).abs() < 1e-9);
+        }
+        // 外接矩形内、平行四边形外的点
+        let r = Ray::new(origin, Point3::new(0.2, 1.0, -0.9) - origin, 0.0);
+        assert!(!light.hit(&r, &ray_t, &mut HitRecord::default()));
+    }
+}
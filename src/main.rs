@@ -1,40 +1,71 @@
 pub mod aabb;
+pub mod animation;
+pub mod atmosphere;
 pub mod bvh;
 pub mod camera;
 pub mod color;
+pub mod conductor;
 pub mod constant_medium;
+pub mod density;
+pub mod exposure;
+pub mod filter;
 pub mod hittable;
 pub mod hittable_list;
+pub mod ies;
 pub mod interval;
+pub mod light;
 pub mod material;
 pub mod mesh;
+pub mod microfacet;
+pub mod onb;
 pub mod perlin;
+pub mod phase;
 pub mod quad;
 pub mod ray;
 pub mod rtw_image;
 pub mod rtweekend;
+pub mod sky;
+pub mod spectrum;
 pub mod sphere;
 pub mod texture;
+pub mod thin_film;
 pub mod triangle;
+pub mod turntable;
 pub mod vec3;
+pub mod voxel_grid;
 
 use crate::quad::Quad;
+use animation::{CameraAnimation, CameraKeyframe, Interpolation};
+use atmosphere::Atmosphere;
 use bvh::BvhNode;
 use camera::Camera;
 use color::Color;
 use constant_medium::ConstantMedium;
+use density::{NoiseDensity, UniformDensity};
+use exposure::PhysicalExposure;
 use hittable::{HitRecord, Hittable, RotateX, RotateY, RotateZ, Scale, Translate};
 use hittable_list::HittableList;
-use material::{Dielectric, DiffuseLight, Lambertian, Metal};
+use ies::IesProfile;
+use light::{AreaLight, DirectionalLight, IesLight, PointLight, ProjectorLight, SpotLight};
+use material::{
+    BumpMapped, Dielectric, DiffuseLight, Lambertian, Material, Metal, MixMaterial, OrenNayar,
+    Subsurface, Translucent,
+};
 use mesh::Mesh;
 use perlin::Perlin;
+use phase::{DoubleHenyeyGreenstein, HenyeyGreenstein};
 use ray::Ray;
 use rtw_image::RtwImage;
+use sky::PreethamSky;
+use spectrum::Dispersion;
 use sphere::Sphere;
 use std::sync::Arc;
-use texture::{CheckerTexture, ImageTexture, NoiseTexture, Texture};
+use texture::{CheckerTexture, ImageTexture, NoiseTexture, ScaledTexture, Texture};
+use thin_film::ThinFilm;
 use triangle::Triangle;
+use turntable::Turntable;
 use vec3::{Point3, Vec3};
+use voxel_grid::{VoxelEmission, VoxelGrid};
 
 fn bouncing_spheres() {
     let mut world = HittableList::default();
@@ -531,10 +562,11 @@ fn final_scene(image_width: i32, samples_per_pixel: usize, max_depth: i32) {
         sphere_material,
     )));
 
+    // 厚玻璃：穿过整个直径后剩下淡绿色
     world.add(Arc::new(Sphere::new_static(
         Point3::new(260.0, 150.0, 45.0),
         50.0,
-        Arc::new(Dielectric::new(1.5)),
+        Arc::new(Dielectric::new(1.5).with_absorption(Color::new(0.8, 0.95, 0.85), 100.0)),
     )));
 
     world.add(Arc::new(Sphere::new_static(
@@ -619,7 +651,7 @@ fn final_scene(image_width: i32, samples_per_pixel: usize, max_depth: i32) {
     cam.render(&world);
 }
 
-fn Manhattanhenge() {
+fn manhattanhenge_world() -> HittableList {
     let mut world = HittableList::default();
 
     let city_model = Arc::new(Mesh::new("assets/source/city.glb"));
@@ -637,7 +669,10 @@ fn Manhattanhenge() {
     let the_car = Arc::new(Scale::new(the_car, Vec3::new(1.55, 1.55, 1.55)));
     let the_car = Arc::new(Translate::new(the_car, Vec3::new(-0.85, 2.2, -22.0)));
     world.add(the_car);
+    world
+}
 
+fn manhattanhenge_camera() -> Camera {
     let mut cam = Camera::default();
     cam.aspect_ratio = 16.0 / 9.0;
     cam.image_width = 800;
@@ -651,6 +686,950 @@ fn Manhattanhenge() {
     cam.vup = Vec3::new(0.0, 0.0, 1.0);
     cam.defocus_angle = 0.0;
     // cam.focus_dist = (Point3::new(80.0, 50.0, 300.0) - Point3::new(0.0, 20.0, 0.0)).length();
+    cam
+}
+
+fn Manhattanhenge() {
+    let world = manhattanhenge_world();
+    let mut cam = manhattanhenge_camera();
+    cam.render(&world);
+}
+
+fn manhattanhenge_flythrough() {
+    let world = manhattanhenge_world();
+    let mut cam = manhattanhenge_camera();
+    cam.samples_per_pixel = 500;
+
+    // 沿街道从远处飞向汽车，再抬高视角看向太阳
+    let mut anim = CameraAnimation::new(Interpolation::CatmullRom);
+    anim.add_keyframe(CameraKeyframe::from_camera(0.0, &cam));
+    cam.lookfrom = Point3::new(-3.0, 0.0, -30.0);
+    anim.add_keyframe(CameraKeyframe::from_camera(40.0, &cam));
+    cam.lookfrom = Point3::new(-1.0, 1.5, -26.0);
+    cam.vfov = 70.0;
+    anim.add_keyframe(CameraKeyframe::from_camera(80.0, &cam));
+    cam.lookfrom = Point3::new(0.0, 4.0, -18.0);
+    cam.lookat = Point3::new(-10.5, 0.0, 100.0);
+    cam.vfov = 60.0;
+    anim.add_keyframe(CameraKeyframe::from_camera(120.0, &cam));
+
+    let frames = anim.frame_range().unwrap();
+    anim.render_sequence(&cam, &world, frames, "frames/manhattanhenge", true)
+        .expect("failed to write frame sequence");
+}
+fn turntable_preview(model_path: &str) {
+    let model = Arc::new(Mesh::new(model_path));
+
+    let mut cam = Camera::default();
+    cam.aspect_ratio = 1.0;
+    cam.image_width = 400;
+    cam.samples_per_pixel = 100;
+    cam.max_depth = 20;
+    cam.vfov = 30.0;
+
+    let turntable = Turntable::new(36, 20.0);
+    turntable
+        .render(model, &cam, "frames/turntable")
+        .expect("failed to write turntable frames");
+}
+fn dispersion() {
+    // 光谱模式下三种色散强度不同的玻璃球：冕牌玻璃、重火石玻璃、钻石
+    let mut world = HittableList::default();
+    let checker = Arc::new(CheckerTexture::from_colors(
+        0.5,
+        Color::new(0.1, 0.1, 0.1),
+        Color::new(0.9, 0.9, 0.9),
+    ));
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Arc::new(Lambertian::new_from_texture(checker)),
+    )));
+    let glasses = [Dispersion::bk7(), Dispersion::sf11(), Dispersion::diamond()];
+    for (i, dispersion) in glasses.into_iter().enumerate() {
+        world.add(Arc::new(Sphere::new_static(
+            Point3::new(-2.5 + 2.5 * i as f64, 1.0, 0.0),
+            1.0,
+            Arc::new(Dielectric::new(1.5).with_dispersion(dispersion)),
+        )));
+    }
+
+    let mut cam = Camera::default();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 800;
+    cam.samples_per_pixel = 500;
+    cam.max_depth = 50;
+    cam.spectral = true;
+
+    cam.vfov = 30.0;
+    cam.lookfrom = Point3::new(0.0, 4.0, 12.0);
+    cam.lookat = Point3::new(0.0, 1.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.render(&world);
+}
+fn mixed_materials() {
+    // 纹理驱动的材质混合：噪声遮罩的锈蚀钢、金与玻璃的棋盘格，以及两者的嵌套混合
+    let mut world = HittableList::default();
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+    )));
+
+    let rust = Arc::new(Lambertian::new(Color::new(0.45, 0.2, 0.08)));
+    let steel = Arc::new(Metal::new_measured(&conductor::IRON, 0.3));
+    let rust_mask = Arc::new(NoiseTexture::new(2.0));
+    let rusty_steel = Arc::new(MixMaterial::new_from_texture(steel, rust, rust_mask));
+
+    let gold = Arc::new(Metal::new_measured(&conductor::GOLD, 0.2));
+    let glass = Arc::new(Dielectric::new(1.5));
+    let checker = Arc::new(CheckerTexture::from_colors(
+        0.25,
+        Color::new(0.0, 0.0, 0.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+    let gold_glass = Arc::new(MixMaterial::new_from_texture(gold, glass, checker.clone()));
+    let nested = Arc::new(MixMaterial::new_from_texture(
+        rusty_steel.clone(),
+        gold_glass.clone(),
+        checker,
+    ));
+
+    let materials: [Arc<dyn Material + Send + Sync>; 3] = [rusty_steel, gold_glass, nested];
+    for (i, mat) in materials.into_iter().enumerate() {
+        world.add(Arc::new(Sphere::new_static(
+            Point3::new(-2.5 + 2.5 * i as f64, 1.0, 0.0),
+            1.0,
+            mat,
+        )));
+    }
+
+    let mut cam = Camera::default();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 800;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+
+    cam.vfov = 30.0;
+    cam.lookfrom = Point3::new(0.0, 4.0, 12.0);
+    cam.lookat = Point3::new(0.0, 1.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.render(&world);
+}
+fn bump_mapping() {
+    // 砖墙用自身图片作为高度贴图，球体用噪声纹理做凹凸，掠射光下可以看到起伏
+    let mut world = HittableList::default();
+    let bricks: Arc<dyn Texture> = Arc::new(
+        ImageTexture::new("assets/textures/Bricks_19.jpeg").expect("failed to load bricks"),
+    );
+    let wall = Arc::new(
+        BumpMapped::new(
+            Arc::new(Lambertian::new_from_texture(bricks.clone())),
+            bricks,
+            0.01,
+        )
+        .with_delta(1.0 / 128.0),
+    );
+    world.add(Arc::new(Quad::new(
+        Point3::new(-3.0, -2.0, 0.0),
+        Vec3::new(6.0, 0.0, 0.0),
+        Vec3::new(0.0, 4.0, 0.0),
+        wall,
+    )));
+
+    let bumpy = Arc::new(BumpMapped::new(
+        Arc::new(Metal::new(Color::new(0.8, 0.8, 0.85), 0.15)),
+        Arc::new(NoiseTexture::new(4.0)),
+        0.05,
+    ));
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(0.0, -1.0, 1.5),
+        1.0,
+        bumpy,
+    )));
+
+    let light = Arc::new(DiffuseLight::new_from_color(Color::new(8.0, 8.0, 8.0)));
+    world.add(Arc::new(Quad::new(
+        Point3::new(-6.0, -2.0, 4.0),
+        Vec3::new(0.0, 0.0, -2.0),
+        Vec3::new(0.0, 4.0, 0.0),
+        light,
+    )));
+
+    let mut cam = Camera::default();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 800;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 20;
+
+    cam.vfov = 40.0;
+    cam.lookfrom = Point3::new(0.0, 0.0, 9.0);
+    cam.lookat = Point3::new(0.0, 0.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.render(&world);
+}
+fn diffuse_models() {
+    // 左：Lambertian，中：Oren–Nayar 粗糙漫反射，右：内部有光源的半透明灯罩
+    let mut world = HittableList::default();
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Arc::new(OrenNayar::new(Color::new(0.5, 0.5, 0.5), 0.5)),
+    )));
+
+    let clay = Color::new(0.75, 0.45, 0.3);
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(-2.5, 1.0, 0.0),
+        1.0,
+        Arc::new(Lambertian::new(clay)),
+    )));
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        Arc::new(OrenNayar::new(clay, 1.0)),
+    )));
+
+    let shade = Arc::new(Translucent::new(
+        Color::new(0.4, 0.4, 0.35),
+        Color::new(0.55, 0.4, 0.2),
+    ));
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(2.5, 1.0, 0.0),
+        1.0,
+        shade,
+    )));
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(2.5, 1.0, 0.0),
+        0.3,
+        Arc::new(DiffuseLight::new_from_color(Color::new(10.0, 10.0, 10.0))),
+    )));
+
+    let light = Arc::new(DiffuseLight::new_from_color(Color::new(6.0, 6.0, 6.0)));
+    world.add(Arc::new(Quad::new(
+        Point3::new(-6.0, 6.0, -2.0),
+        Vec3::new(4.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 4.0),
+        light,
+    )));
+
+    let mut cam = Camera::default();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 800;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 20;
+
+    cam.vfov = 30.0;
+    cam.lookfrom = Point3::new(0.0, 3.0, 12.0);
+    cam.lookat = Point3::new(0.0, 1.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.render(&world);
+}
+fn subsurface() {
+    // 次表面散射：大理石、牛奶、玉石。自由程较短的材质需要较大的 max_depth
+    let mut world = HittableList::default();
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Arc::new(Lambertian::new(Color::new(0.4, 0.4, 0.4))),
+    )));
+
+    let materials: [Arc<dyn Material + Send + Sync>; 3] = [
+        Arc::new(Subsurface::from_albedo(
+            1.5,
+            Color::new(0.9, 0.88, 0.85),
+            Color::new(0.2, 0.18, 0.15),
+        )),
+        Arc::new(
+            Subsurface::from_albedo(
+                1.35,
+                Color::new(0.95, 0.93, 0.85),
+                Color::new(0.08, 0.06, 0.04),
+            )
+            .with_anisotropy(0.8),
+        ),
+        Arc::new(
+            Subsurface::from_albedo(1.6, Color::new(0.3, 0.7, 0.4), Color::new(0.5, 0.9, 0.6))
+                .with_anisotropy(0.3),
+        ),
+    ];
+    for (i, mat) in materials.into_iter().enumerate() {
+        world.add(Arc::new(Sphere::new_static(
+            Point3::new(-2.5 + 2.5 * i as f64, 1.0, 0.0),
+            1.0,
+            mat,
+        )));
+    }
+
+    let light = Arc::new(DiffuseLight::new_from_color(Color::new(8.0, 8.0, 8.0)));
+    world.add(Arc::new(Quad::new(
+        Point3::new(-1.0, 5.0, -4.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 2.0),
+        light,
+    )));
+
+    let mut cam = Camera::default();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 800;
+    cam.samples_per_pixel = 500;
+    cam.max_depth = 256;
+
+    cam.vfov = 30.0;
+    cam.lookfrom = Point3::new(0.0, 3.0, 12.0);
+    cam.lookat = Point3::new(0.0, 1.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.render(&world);
+}
+fn thin_film() {
+    // 薄膜干涉：肥皂泡（空气中的水膜）、油膜覆盖的深色金属、均匀氧化层的钛
+    let mut world = HittableList::default();
+    let checker = Arc::new(CheckerTexture::from_colors(
+        0.5,
+        Color::new(0.1, 0.1, 0.1),
+        Color::new(0.9, 0.9, 0.9),
+    ));
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Arc::new(Lambertian::new_from_texture(checker)),
+    )));
+
+    // 厚度 0..800nm 随噪声变化
+    let swirl = Arc::new(ScaledTexture::new(
+        Arc::new(NoiseTexture::new(2.0)),
+        Color::new(800.0, 800.0, 800.0),
+    ));
+    let bubble =
+        Dielectric::new(1.0).with_thin_film(ThinFilm::new_from_texture(swirl.clone(), 1.33));
+    let oil = Metal::new(Color::new(0.05, 0.05, 0.05), 0.05)
+        .with_thin_film(ThinFilm::new_from_texture(swirl, 1.45));
+    let titanium = Metal::from_name("titanium", 0.1)
+        .expect("unknown conductor")
+        .with_thin_film(ThinFilm::new(350.0, 2.4));
+    let materials: [Arc<dyn Material + Send + Sync>; 3] =
+        [Arc::new(bubble), Arc::new(oil), Arc::new(titanium)];
+    for (i, mat) in materials.into_iter().enumerate() {
+        world.add(Arc::new(Sphere::new_static(
+            Point3::new(-2.5 + 2.5 * i as f64, 1.0, 0.0),
+            1.0,
+            mat,
+        )));
+    }
+
+    let mut cam = Camera::default();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 800;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+
+    cam.vfov = 30.0;
+    cam.lookfrom = Point3::new(0.0, 3.0, 12.0);
+    cam.lookat = Point3::new(0.0, 1.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.render(&world);
+}
+fn conductors() {
+    // 内置实测导体按名字排成一排，上排光滑、下排粗糙
+    let mut world = HittableList::default();
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+    )));
+
+    let names = [
+        "gold", "silver", "copper", "aluminum", "chrome", "iron", "titanium",
+    ];
+    for (i, name) in names.iter().enumerate() {
+        for (row, roughness) in [0.05, 0.35].into_iter().enumerate() {
+            let metal = Metal::from_name(name, roughness).expect("unknown conductor");
+            world.add(Arc::new(Sphere::new_static(
+                Point3::new(-6.0 + 2.0 * i as f64, 0.8, -2.0 * row as f64),
+                0.8,
+                Arc::new(metal),
+            )));
+        }
+    }
+
+    let mut cam = Camera::default();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 800;
+    cam.samples_per_pixel = 200;
+    cam.max_depth = 50;
+
+    cam.vfov = 35.0;
+    cam.lookfrom = Point3::new(0.0, 4.0, 14.0);
+    cam.lookat = Point3::new(0.0, 0.5, -1.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.render(&world);
+}
+fn participating_media() {
+    // 非均匀介质：噪声密度的云（双瓣 HG 相函数）和各通道散射不同的蓝色雾球
+    let mut world = HittableList::default();
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+    )));
+
+    let boundary_mat = Arc::new(Dielectric::new(1.5));
+    let cloud_boundary = Arc::new(Sphere::new_static(
+        Point3::new(-1.8, 1.6, 0.0),
+        1.5,
+        boundary_mat.clone(),
+    ));
+    let cloud_density = Arc::new(NoiseDensity::new(12.0, 1.5).with_threshold(0.1));
+    world.add(Arc::new(
+        ConstantMedium::new_heterogeneous(
+            cloud_boundary,
+            cloud_density,
+            Color::new(0.02, 0.02, 0.02),
+            Color::new(1.0, 1.0, 1.0),
+        )
+        .with_phase(Arc::new(DoubleHenyeyGreenstein::new(0.8, -0.3, 0.85))),
+    ));
+
+    // 散射系数随波长递减（类似瑞利散射）：散射光偏蓝，透射光偏红
+    let haze_boundary = Arc::new(Sphere::new_static(
+        Point3::new(1.8, 1.2, 0.0),
+        1.2,
+        boundary_mat,
+    ));
+    world.add(Arc::new(
+        ConstantMedium::new_heterogeneous(
+            haze_boundary,
+            Arc::new(UniformDensity::new(1.0)),
+            Color::new(0.0, 0.0, 0.0),
+            Color::new(0.3, 0.8, 2.0),
+        )
+        .with_phase(Arc::new(HenyeyGreenstein::new(0.3))),
+    ));
+
+    let light = Arc::new(DiffuseLight::new_from_color(Color::new(8.0, 8.0, 8.0)));
+    world.add(Arc::new(Quad::new(
+        Point3::new(-1.0, 6.0, -3.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 2.0),
+        light,
+    )));
+
+    let mut cam = Camera::default();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 800;
+    cam.samples_per_pixel = 500;
+    cam.max_depth = 100;
+
+    cam.vfov = 30.0;
+    cam.lookfrom = Point3::new(0.0, 3.0, 12.0);
+    cam.lookat = Point3::new(0.0, 1.2, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.render(&world);
+}
+fn voxel_fire() {
+    // 程序生成的烟柱体素网格：密度随高度扩散，底部核心温度高，按黑体辐射发光。
+    // 同样的网格也可以用 VoxelGrid::load_raw / load_sparse 从模拟导出的文件读取
+    let mut world = HittableList::default();
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Arc::new(Lambertian::new(Color::new(0.4, 0.4, 0.4))),
+    )));
+
+    let res = [48, 64, 48];
+    let noise = Perlin::new();
+    let mut density = Vec::with_capacity(res[0] * res[1] * res[2]);
+    let mut temperature = Vec::with_capacity(density.capacity());
+    for z in 0..res[2] {
+        for y in 0..res[1] {
+            for x in 0..res[0] {
+                let h = y as f64 / res[1] as f64;
+                let dx = x as f64 / res[0] as f64 - 0.5;
+                let dz = z as f64 / res[2] as f64 - 0.5;
+                let r = (dx * dx + dz * dz).sqrt();
+                let p = Point3::new(x as f64, y as f64 * 0.7, z as f64) * 0.12;
+                let turb = noise.turb(&p, 5);
+                let radius = 0.15 + 0.3 * h;
+                let d = ((radius - r) / radius).clamp(0.0, 1.0) * (1.0 - h) * (0.4 + turb);
+                density.push(d as f32);
+                let core = ((0.15 - r) / 0.15).clamp(0.0, 1.0) * (1.0 - 2.0 * h).max(0.0);
+                temperature.push((core * (0.6 + 0.6 * turb)) as f32);
+            }
+        }
+    }
+
+    let (min, max) = (Point3::new(-1.5, 0.0, -1.5), Point3::new(1.5, 4.0, 1.5));
+    let density = Arc::new(VoxelGrid::new(res, density).with_bounds(min, max));
+    let temperature = Arc::new(VoxelGrid::new(res, temperature).with_bounds(min, max));
+    let (bmin, bmax) = density.bounds();
+    let boundary = Arc::new(quad::boxx(
+        bmin,
+        bmax,
+        Arc::new(Lambertian::new(Color::new(1.0, 1.0, 1.0))),
+    ));
+    world.add(Arc::new(
+        ConstantMedium::new_heterogeneous(
+            boundary,
+            density,
+            Color::new(4.0, 4.0, 4.0),
+            Color::new(4.0, 4.0, 4.0),
+        )
+        .with_phase(Arc::new(HenyeyGreenstein::new(0.4)))
+        .with_emission(Arc::new(VoxelEmission::from_temperature(
+            temperature,
+            2500.0,
+            1.0,
+        ))),
+    ));
+
+    let mut cam = Camera::default();
+    cam.aspect_ratio = 1.0;
+    cam.image_width = 600;
+    cam.samples_per_pixel = 300;
+    cam.max_depth = 50;
+
+    cam.vfov = 40.0;
+    cam.lookfrom = Point3::new(0.0, 2.5, 9.0);
+    cam.lookat = Point3::new(0.0, 1.8, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.render(&world);
+}
+fn street_canyon_world() -> HittableList {
+    let mut world = HittableList::default();
+    world.add(Arc::new(Quad::new(
+        Point3::new(-500.0, 0.0, 100.0),
+        Vec3::new(1000.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -2000.0),
+        Arc::new(Lambertian::new(Color::new(0.3, 0.3, 0.3))),
+    )));
+
+    // 沿 -z 延伸的街道，两侧的楼群在路口留出空隙让阳光斜射进来
+    let facade = Arc::new(Lambertian::new(Color::new(0.55, 0.5, 0.45)));
+    let mut buildings = HittableList::default();
+    let mut z = 0.0;
+    while z > -900.0 {
+        let depth = rtweekend::random_double_range(30.0, 60.0);
+        for side in [-1.0, 1.0] {
+            let height = rtweekend::random_double_range(30.0, 90.0);
+            let (x0, x1) = if side < 0.0 {
+                (-40.0, -12.0)
+            } else {
+                (12.0, 40.0)
+            };
+            buildings.add(Arc::new(quad::boxx(
+                Point3::new(x0, 0.0, z - depth),
+                Point3::new(x1, height, z),
+                facade.clone(),
+            )));
+        }
+        z -= depth + 12.0;
+    }
+    world.add(Arc::new(BvhNode::new(&mut buildings.objects)));
+    world
+}
+
+fn street_canyon_camera() -> Camera {
+    let mut cam = Camera::default();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 800;
+    cam.samples_per_pixel = 500;
+    cam.max_depth = 20;
+
+    cam.vfov = 35.0;
+    cam.lookfrom = Point3::new(0.0, 6.0, 40.0);
+    cam.lookat = Point3::new(0.0, 30.0, -900.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam
+}
+
+fn street_canyon_haze() {
+    // 街道峡谷中的低角度太阳：全局薄霾带来体积光束和远处的空气透视
+    let mut world = street_canyon_world();
+    // 街道尽头贴近地平线的太阳
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(0.0, 60.0, -1900.0),
+        100.0,
+        Arc::new(DiffuseLight::new_from_color(Color::new(40.0, 28.0, 14.0))),
+    )));
+
+    let mut cam = street_canyon_camera();
+    cam.atmosphere = Some(Arc::new(
+        Atmosphere::new(
+            1.0,
+            Color::new(0.0005, 0.0005, 0.0005),
+            Color::new(0.0015, 0.002, 0.003),
+        )
+        .with_height_falloff(Vec3::new(0.0, 1.0, 0.0), 0.0, 80.0)
+        .with_phase(Arc::new(HenyeyGreenstein::new(0.7))),
+    ));
+    cam.render(&world);
+}
+
+fn manhattanhenge_sky() {
+    // 纽约 5 月 29 日日落（UTC 5 月 30 日 00:12），太阳方位约 299°，恰好沿曼哈顿街道网格落下。
+    // 街道沿 -z，把北方转到使方位角 299° 对应 -z 的方向
+    let azimuth = 299.0_f64.to_radians();
+    let north = Vec3::new(-azimuth.sin(), 0.0, -azimuth.cos());
+    let sky = PreethamSky::from_location(40.78, -73.97, 150, 0.2, 3.0).with_north(north);
+
+    let world = street_canyon_world();
+    let mut cam = street_canyon_camera();
+    cam.sky = Some(Arc::new(sky));
+    cam.atmosphere = Some(Arc::new(
+        Atmosphere::new(
+            1.0,
+            Color::new(0.0005, 0.0005, 0.0005),
+            Color::new(0.0015, 0.002, 0.003),
+        )
+        .with_height_falloff(Vec3::new(0.0, 1.0, 0.0), 0.0, 80.0)
+        .with_phase(Arc::new(HenyeyGreenstein::new(0.7))),
+    ));
+    // 天空输出为 cd/m²，用物理曝光映射到像素值
+    cam.physical = Some(PhysicalExposure::new(24.0, 8.0, 1.0 / 60.0, 100.0));
+    cam.lookat = Point3::new(0.0, 12.0, -900.0);
+    cam.render(&world);
+}
+fn analytic_lights() {
+    // 封闭房间里的解析光源：从窗口斜射进来的平行光、朝下的顶灯面光源、
+    // 打在球上的聚光灯、角落的点光源和双面圆盘灯，都通过阴影光线直接采样
+    let mut world = HittableList::default();
+    let white = Arc::new(Lambertian::new(Color::new(0.73, 0.73, 0.73)));
+    let red = Arc::new(Lambertian::new(Color::new(0.65, 0.05, 0.05)));
+    let green = Arc::new(Lambertian::new(Color::new(0.12, 0.45, 0.15)));
+    let wall = |q: Point3, u: Vec3, v: Vec3, mat: Arc<Lambertian>| -> Arc<Quad> {
+        Arc::new(Quad::new(q, u, v, mat))
+    };
+
+    // 地面、天花板、后墙和右墙
+    world.add(wall(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(10.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 10.0),
+        white.clone(),
+    ));
+    world.add(wall(
+        Point3::new(0.0, 6.0, 0.0),
+        Vec3::new(10.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 10.0),
+        white.clone(),
+    ));
+    world.add(wall(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(10.0, 0.0, 0.0),
+        Vec3::new(0.0, 6.0, 0.0),
+        white.clone(),
+    ));
+    world.add(wall(
+        Point3::new(0.0, 0.0, 10.0),
+        Vec3::new(10.0, 0.0, 0.0),
+        Vec3::new(0.0, 6.0, 0.0),
+        white.clone(),
+    ));
+    world.add(wall(
+        Point3::new(10.0, 0.0, 0.0),
+        Vec3::new(0.0, 6.0, 0.0),
+        Vec3::new(0.0, 0.0, 10.0),
+        green,
+    ));
+    // 左墙在 y ∈ [2, 4.5]、z ∈ [3, 7] 留出窗口
+    world.add(wall(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.0, 0.0),
+        Vec3::new(0.0, 0.0, 10.0),
+        red.clone(),
+    ));
+    world.add(wall(
+        Point3::new(0.0, 4.5, 0.0),
+        Vec3::new(0.0, 1.5, 0.0),
+        Vec3::new(0.0, 0.0, 10.0),
+        red.clone(),
+    ));
+    world.add(wall(
+        Point3::new(0.0, 2.0, 0.0),
+        Vec3::new(0.0, 2.5, 0.0),
+        Vec3::new(0.0, 0.0, 3.0),
+        red.clone(),
+    ));
+    world.add(wall(
+        Point3::new(0.0, 2.0, 7.0),
+        Vec3::new(0.0, 2.5, 0.0),
+        Vec3::new(0.0, 0.0, 3.0),
+        red,
+    ));
+
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(6.5, 1.2, 3.5),
+        1.2,
+        white.clone(),
+    )));
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(3.0, 0.8, 5.0),
+        0.8,
+        Arc::new(Metal::new(Color::new(0.8, 0.8, 0.8), 0.1)),
+    )));
+    world.add(Arc::new(quad::boxx(
+        Point3::new(1.5, 0.0, 1.0),
+        Point3::new(3.0, 2.5, 2.5),
+        white,
+    )));
+
+    let mut cam = Camera::default();
+    // 穿过窗口的阳光，带 0.53° 的太阳圆盘形成软化的窗影
+    cam.lights.push(Arc::new(
+        DirectionalLight::new(Vec3::new(-1.0, 0.8, 0.2), Color::new(1.0, 0.95, 0.85), 3.0)
+            .with_angular_diameter(0.53),
+    ));
+    // 天花板中央的单面面光源，法线 u × v 朝下
+    let ceiling = Arc::new(AreaLight::quad(
+        Point3::new(4.0, 5.99, 4.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 2.0),
+        Color::new(1.5, 1.5, 1.5),
+    ));
+    world.add(ceiling.clone());
+    cam.lights.push(ceiling);
+    cam.lights.push(Arc::new(SpotLight::new(
+        Point3::new(9.0, 5.5, 1.0),
+        Point3::new(6.5, 1.2, 3.5) - Point3::new(9.0, 5.5, 1.0),
+        Color::new(1.0, 0.8, 0.5),
+        150.0,
+        10.0,
+        18.0,
+    )));
+    cam.lights.push(Arc::new(PointLight::new(
+        Point3::new(1.0, 1.0, 1.0),
+        Color::new(0.4, 0.6, 1.0),
+        3.0,
+    )));
+    // 悬在空中的双面圆盘灯，按光通量（lm）给定亮度
+    let disk = Arc::new(
+        AreaLight::disk(
+            Point3::new(8.0, 4.0, 8.5),
+            Vec3::new(-1.0, 0.0, -1.0),
+            0.4,
+            Color::new(1.0, 0.6, 0.9),
+        )
+        .with_two_sided(true)
+        .with_power(20.0),
+    );
+    world.add(disk.clone());
+    cam.lights.push(disk);
+
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 800;
+    cam.samples_per_pixel = 300;
+    cam.max_depth = 20;
+
+    cam.vfov = 60.0;
+    cam.lookfrom = Point3::new(7.0, 3.0, 9.8);
+    cam.lookat = Point3::new(3.5, 2.8, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.render(&world);
+}
+fn ies_gobo() {
+    // 展厅：三盏 IES 配光的蝙蝠翼筒灯靠墙布置，在墙面上洗出扇形光斑；
+    // 一盏投影灯把铁格栅纹理作为 gobo 图案投到地面上
+    let mut world = HittableList::default();
+    let white = Arc::new(Lambertian::new(Color::new(0.75, 0.75, 0.75)));
+    let floor = Arc::new(Lambertian::new(Color::new(0.45, 0.4, 0.35)));
+    world.add(Arc::new(Quad::new(
+        Point3::new(-6.0, 0.0, 0.0),
+        Vec3::new(12.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 10.0),
+        floor,
+    )));
+    for (q, u, v) in [
+        // 天花板、后墙、前墙、左墙、右墙
+        (
+            Point3::new(-6.0, 4.0, 0.0),
+            Vec3::new(12.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 10.0),
+        ),
+        (
+            Point3::new(-6.0, 0.0, 0.0),
+            Vec3::new(12.0, 0.0, 0.0),
+            Vec3::new(0.0, 4.0, 0.0),
+        ),
+        (
+            Point3::new(-6.0, 0.0, 10.0),
+            Vec3::new(12.0, 0.0, 0.0),
+            Vec3::new(0.0, 4.0, 0.0),
+        ),
+        (
+            Point3::new(-6.0, 0.0, 0.0),
+            Vec3::new(0.0, 4.0, 0.0),
+            Vec3::new(0.0, 0.0, 10.0),
+        ),
+        (
+            Point3::new(6.0, 0.0, 0.0),
+            Vec3::new(0.0, 4.0, 0.0),
+            Vec3::new(0.0, 0.0, 10.0),
+        ),
+    ] {
+        world.add(Arc::new(Quad::new(q, u, v, white.clone())));
+    }
+    world.add(Arc::new(quad::boxx(
+        Point3::new(-0.5, 0.0, 1.0),
+        Point3::new(0.5, 1.0, 2.0),
+        white.clone(),
+    )));
+    world.add(Arc::new(Sphere::new_static(
+        Point3::new(0.0, 1.5, 1.5),
+        0.5,
+        Arc::new(Metal::new(Color::new(0.9, 0.75, 0.5), 0.2)),
+    )));
+
+    let mut cam = Camera::default();
+    let profile = Arc::new(
+        IesProfile::load("assets/ies/downlight_batwing.ies").expect("failed to load IES profile"),
+    );
+    for x in [-4.0, 0.0, 4.0] {
+        // 配光较宽的 0° 平面朝向墙面
+        cam.lights.push(Arc::new(
+            IesLight::new(
+                Point3::new(x, 3.95, 0.8),
+                Vec3::new(0.0, -1.0, 0.0),
+                profile.clone(),
+            )
+            .with_reference(Vec3::new(0.0, 0.0, -1.0))
+            .with_color(Color::new(1.0, 0.85, 0.7))
+            .with_power(70.0),
+        ));
+    }
+    let gobo =
+        Arc::new(ImageTexture::new("assets/textures/u2grt1_5.jpeg").expect("failed to load gobo"));
+    cam.lights.push(Arc::new(ProjectorLight::new(
+        Point3::new(3.0, 3.8, 8.0),
+        Point3::new(-0.5, 0.0, 4.5) - Point3::new(3.0, 3.8, 8.0),
+        gobo,
+        150.0,
+        25.0,
+    )));
+
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 800;
+    cam.samples_per_pixel = 300;
+    cam.max_depth = 20;
+
+    cam.vfov = 60.0;
+    cam.lookfrom = Point3::new(0.0, 1.8, 9.0);
+    cam.lookat = Point3::new(0.0, 1.4, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.render(&world);
+}
+fn room_world(white: Arc<Lambertian>) -> HittableList {
+    // 14 m × 14 m、高 5 m 的封闭白色房间
+    let mut world = HittableList::default();
+    for (q, u, v) in [
+        // 地面、天花板、后墙、前墙、左墙、右墙
+        (
+            Point3::new(-7.0, 0.0, -4.0),
+            Vec3::new(14.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 14.0),
+        ),
+        (
+            Point3::new(-7.0, 5.0, -4.0),
+            Vec3::new(14.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 14.0),
+        ),
+        (
+            Point3::new(-7.0, 0.0, -4.0),
+            Vec3::new(14.0, 0.0, 0.0),
+            Vec3::new(0.0, 5.0, 0.0),
+        ),
+        (
+            Point3::new(-7.0, 0.0, 10.0),
+            Vec3::new(14.0, 0.0, 0.0),
+            Vec3::new(0.0, 5.0, 0.0),
+        ),
+        (
+            Point3::new(-7.0, 0.0, -4.0),
+            Vec3::new(0.0, 5.0, 0.0),
+            Vec3::new(0.0, 0.0, 14.0),
+        ),
+        (
+            Point3::new(7.0, 0.0, -4.0),
+            Vec3::new(0.0, 5.0, 0.0),
+            Vec3::new(0.0, 0.0, 14.0),
+        ),
+    ] {
+        world.add(Arc::new(Quad::new(q, u, v, white.clone())));
+    }
+    world
+}
+fn blackbody_lights() {
+    // 亮度相同、色温从烛光到蓝天的一排黑体发光球，以及按光通量给定的暖白顶灯
+    let white = Arc::new(Lambertian::new(Color::new(0.7, 0.7, 0.7)));
+    let mut world = room_world(white.clone());
+
+    for (i, kelvin) in [1900.0, 2700.0, 4000.0, 5500.0, 6500.0, 10000.0]
+        .into_iter()
+        .enumerate()
+    {
+        let x = -5.0 + 2.0 * i as f64;
+        world.add(Arc::new(Sphere::new_static(
+            Point3::new(x, 0.6, 0.0),
+            0.4,
+            Arc::new(DiffuseLight::new_from_temperature(kelvin, 4.0)),
+        )));
+        // 发光球后方的白色立柱显示各自的光色
+        world.add(Arc::new(quad::boxx(
+            Point3::new(x - 0.4, 0.0, -1.4),
+            Point3::new(x + 0.4, 2.5, -0.6),
+            white.clone(),
+        )));
+    }
+
+    // 1 m × 1 m 的 3000K 顶灯，法线 u × v 朝下，只向下发光
+    world.add(Arc::new(Quad::new(
+        Point3::new(-0.5, 4.99, 2.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        Arc::new(DiffuseLight::new_from_color(spectrum::blackbody(3000.0)).with_power(6.0, 1.0)),
+    )));
+
+    let mut cam = Camera::default();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 800;
+    cam.samples_per_pixel = 500;
+    cam.max_depth = 20;
+
+    cam.vfov = 50.0;
+    cam.lookfrom = Point3::new(0.0, 2.2, 9.5);
+    cam.lookat = Point3::new(0.0, 1.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
+    cam.render(&world);
+}
+fn emission_profiles() {
+    // 三块相同亮度的顶灯，cosⁿ 方向分布的指数依次为 0（朗伯）、8、64，地面上的光斑逐渐收紧；
+    // 中间悬挂一块两面发光的竖直灯板
+    let mut world = room_world(Arc::new(Lambertian::new(Color::new(0.7, 0.7, 0.7))));
+
+    for (x, exponent) in [(-4.5, 0.0), (0.0, 8.0), (4.5, 64.0)] {
+        // 法线 u × v 朝下
+        world.add(Arc::new(Quad::new(
+            Point3::new(x - 0.75, 4.99, -0.75),
+            Vec3::new(1.5, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.5),
+            Arc::new(DiffuseLight::new_from_temperature(4000.0, 20.0).with_cosine_power(exponent)),
+        )));
+    }
+    world.add(Arc::new(Quad::new(
+        Point3::new(-2.25, 1.5, 2.5),
+        Vec3::new(0.0, 0.0, 1.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Arc::new(DiffuseLight::new_from_temperature(2700.0, 3.0).with_two_sided(true)),
+    )));
+
+    let mut cam = Camera::default();
+    cam.aspect_ratio = 16.0 / 9.0;
+    cam.image_width = 800;
+    cam.samples_per_pixel = 500;
+    cam.max_depth = 20;
+
+    cam.vfov = 55.0;
+    cam.lookfrom = Point3::new(0.0, 3.5, 9.5);
+    cam.lookat = Point3::new(0.0, 1.0, 0.0);
+    cam.vup = Vec3::new(0.0, 1.0, 0.0);
     cam.render(&world);
 }
 fn main() {
@@ -667,6 +1646,23 @@ fn main() {
         8 => cornell_smoke(),
         9 => final_scene(800, 10000, 40),
         10 => Manhattanhenge(),
+        11 => manhattanhenge_flythrough(),
+        12 => turntable_preview("assets/source/car.glb"),
+        14 => dispersion(),
+        15 => mixed_materials(),
+        16 => bump_mapping(),
+        17 => diffuse_models(),
+        18 => subsurface(),
+        19 => thin_film(),
+        20 => conductors(),
+        21 => participating_media(),
+        22 => voxel_fire(),
+        23 => street_canyon_haze(),
+        24 => manhattanhenge_sky(),
+        25 => analytic_lights(),
+        26 => ies_gobo(),
+        27 => blackbody_lights(),
+        28 => emission_profiles(),
         _ => final_scene(400, 250, 4),
     }
 }
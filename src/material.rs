@@ -1,9 +1,15 @@
 // material.rs
 use crate::color::Color;
+use crate::conductor::ConductorSpectrum;
 use crate::hittable::HitRecord;
+use crate::microfacet::{self, Fresnel, TrowbridgeReitz};
+use crate::onb::Onb;
+use crate::phase::{HenyeyGreenstein, PhaseFunction};
 use crate::ray::Ray;
 use crate::rtweekend;
+use crate::spectrum::{self, Dispersion};
 use crate::texture::{SolidColor, Texture};
+use crate::thin_film::{self, Complex, ThinFilm};
 use crate::vec3;
 use crate::vec3::{Point3, Vec3};
 use std::sync::Arc;
@@ -19,9 +25,21 @@ pub trait Material: Send + Sync {
         scattered: &mut Ray,
     ) -> bool;
 
-    fn emitted(&self, u: f64, v: f64, p: &Point3) -> Color {
+    /// 沿 r_in 看到的 rec 处的自发光辐亮度，可以依赖击中的是正面还是背面、以及出射方向
+    fn emitted(&self, _r_in: &Ray, _rec: &HitRecord) -> Color {
         Color::default()
     }
+
+    /// 沿 direction（离开表面）散射的 BSDF · |cosθ|（体散射为相函数值 · 反照率），用于光源采样；
+    /// None 表示无法按方向求值（镜面、光泽等），此时由 BSDF 采样命中光源计入光照
+    fn eval(&self, _r_in: &Ray, _rec: &HitRecord, _direction: Vec3) -> Option<Color> {
+        None
+    }
+
+    /// 散射方向或权重是否依赖具体波长（无法用 RGB 表达）；光谱模式下遇到这类事件后只保留 hero 波长
+    fn dispersive(&self) -> bool {
+        false
+    }
 }
 
 impl Default for Lambertian {
@@ -65,26 +83,253 @@ impl Material for Lambertian {
         *attenuation = self.tex.value(rec.u, rec.v, &rec.p);
         true
     }
+
+    fn eval(&self, _r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        let cos = vec3::dot(vec3::unit_vector(direction), rec.normal).max(0.0);
+        Some(cos / rtweekend::PI * self.tex.value(rec.u, rec.v, &rec.p))
+    }
 }
-// Metal  （镜面反射）
-pub struct Metal {
+
+// OrenNayar  粗糙漫反射，适合混凝土、黏土等表面。采用 Fujii 改进的 Oren–Nayar 模型，
+// 在整个参数范围内不产生能量。sigma 为粗糙度（取 x 通道，0..1），为 0 时退化为 Lambertian
+pub struct OrenNayar {
     pub albedo: Arc<dyn Texture + Send + Sync>,
-    pub fuzz: f64,
+    pub sigma: Arc<dyn Texture + Send + Sync>,
+}
+
+impl OrenNayar {
+    pub fn new(c: Color, sigma: f64) -> Self {
+        Self::new_from_texture(Arc::new(SolidColor::new(c)), gray_texture(sigma))
+    }
+
+    pub fn new_from_texture(
+        albedo: Arc<dyn Texture + Send + Sync>,
+        sigma: Arc<dyn Texture + Send + Sync>,
+    ) -> Self {
+        Self { albedo, sigma }
+    }
+}
+
+impl Material for OrenNayar {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let wo = -vec3::unit_vector(r_in.direction());
+        let cos_o = vec3::dot(wo, rec.normal);
+        if cos_o <= 0.0 {
+            return false;
+        }
+
+        let mut dir = rec.normal + vec3::random_unit_vector();
+        if dir.near_zero() {
+            dir = rec.normal;
+        }
+        let wi = vec3::unit_vector(dir);
+        let cos_i = vec3::dot(wi, rec.normal);
+
+        // f = ρ (A + B · s / t)，余弦采样的 cosθi / π 相消后权重为 π ρ (A + B · s / t)
+        let sigma = self.sigma.value(rec.u, rec.v, &rec.p).x.clamp(0.0, 1.0);
+        let a = 1.0 / (1.0 + (0.5 - 2.0 / (3.0 * rtweekend::PI)) * sigma);
+        let b = sigma * a;
+        let s = vec3::dot(wi, wo) - cos_i * cos_o;
+        let t = if s > 0.0 { cos_i.max(cos_o) } else { 1.0 };
+
+        *scattered = Ray::new(rec.p, wi, r_in.time());
+        *attenuation = (a + b * s / t) * self.albedo.value(rec.u, rec.v, &rec.p);
+        true
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        let wo = -vec3::unit_vector(r_in.direction());
+        let wi = vec3::unit_vector(direction);
+        let (cos_o, cos_i) = (vec3::dot(wo, rec.normal), vec3::dot(wi, rec.normal));
+        if cos_o <= 0.0 || cos_i <= 0.0 {
+            return Some(Color::default());
+        }
+        let sigma = self.sigma.value(rec.u, rec.v, &rec.p).x.clamp(0.0, 1.0);
+        let a = 1.0 / (1.0 + (0.5 - 2.0 / (3.0 * rtweekend::PI)) * sigma);
+        let b = sigma * a;
+        let s = vec3::dot(wi, wo) - cos_i * cos_o;
+        let t = if s > 0.0 { cos_i.max(cos_o) } else { 1.0 };
+        Some((a + b * s / t) * cos_i / rtweekend::PI * self.albedo.value(rec.u, rec.v, &rec.p))
+    }
+}
+
+// Translucent  薄层漫透射（树叶、灯罩、蜡、皮肤的廉价近似）：
+// 一部分光按 reflectance 漫反射，另一部分按 transmittance 从背面漫射出去。
+// 两者之和不应超过 1，按各自平均值的比例随机选择
+pub struct Translucent {
+    pub reflectance: Arc<dyn Texture + Send + Sync>,
+    pub transmittance: Arc<dyn Texture + Send + Sync>,
+}
+
+impl Translucent {
+    pub fn new(reflectance: Color, transmittance: Color) -> Self {
+        Self::new_from_texture(
+            Arc::new(SolidColor::new(reflectance)),
+            Arc::new(SolidColor::new(transmittance)),
+        )
+    }
+
+    pub fn new_from_texture(
+        reflectance: Arc<dyn Texture + Send + Sync>,
+        transmittance: Arc<dyn Texture + Send + Sync>,
+    ) -> Self {
+        Self {
+            reflectance,
+            transmittance,
+        }
+    }
+}
+
+impl Material for Translucent {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let r = self.reflectance.value(rec.u, rec.v, &rec.p);
+        let t = self.transmittance.value(rec.u, rec.v, &rec.p);
+        let r_avg = (r.x + r.y + r.z) / 3.0;
+        let t_avg = (t.x + t.y + t.z) / 3.0;
+        if r_avg + t_avg <= 0.0 {
+            return false;
+        }
+
+        // 透射时在背面半球余弦采样
+        let prob_r = r_avg / (r_avg + t_avg);
+        let (side, weight) = if rtweekend::random_double() < prob_r {
+            (rec.normal, r / prob_r)
+        } else {
+            (-rec.normal, t / (1.0 - prob_r))
+        };
+        let mut dir = side + vec3::random_unit_vector();
+        if dir.near_zero() {
+            dir = side;
+        }
+        *scattered = Ray::new(rec.p, dir, r_in.time());
+        *attenuation = weight;
+        true
+    }
+
+    fn eval(&self, _r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        let cos = vec3::dot(vec3::unit_vector(direction), rec.normal);
+        let albedo = if cos >= 0.0 {
+            self.reflectance.value(rec.u, rec.v, &rec.p)
+        } else {
+            self.transmittance.value(rec.u, rec.v, &rec.p)
+        };
+        Some(cos.abs() / rtweekend::PI * albedo)
+    }
+}
+
+// Metal  GGX 微表面导体
+pub struct Metal {
+    pub albedo: Arc<dyn Texture + Send + Sync>, // Schlick 模式下为 F0，复折射率模式下为色调
+    pub roughness: Arc<dyn Texture + Send + Sync>, // 感知粗糙度（取 x 通道），alpha = roughness²
+    pub fresnel: Fresnel,
+    pub thin_film: Option<ThinFilm>, // 表面薄膜（氧化层、油膜），产生虹彩
 }
 
 impl Metal {
-    pub fn new(a: Color, f: f64) -> Self {
+    pub fn new(a: Color, roughness: f64) -> Self {
+        Self::new_from_texture(Arc::new(SolidColor::new(a)), roughness)
+    }
+    pub fn new_from_texture(texture: Arc<dyn Texture + Send + Sync>, roughness: f64) -> Self {
+        let r = roughness.clamp(0.0, 1.0);
+        Self::new_from_textures(texture, Arc::new(SolidColor::new(Color::new(r, r, r))))
+    }
+    pub fn new_from_textures(
+        albedo: Arc<dyn Texture + Send + Sync>,
+        roughness: Arc<dyn Texture + Send + Sync>,
+    ) -> Self {
         Self {
-            albedo: Arc::new(SolidColor::new(a)),
-            fuzz: f.min(1.0),
+            albedo,
+            roughness,
+            fresnel: Fresnel::Schlick,
+            thin_film: None,
         }
     }
-    pub fn new_from_texture(texture: Arc<dyn Texture + Send + Sync>, f: f64) -> Self {
+    /// 使用内置实测光谱的导体，如 Metal::new_measured(&conductor::GOLD, 0.1)
+    pub fn new_measured(spectrum: &'static ConductorSpectrum, roughness: f64) -> Self {
         Self {
-            albedo: texture,
-            fuzz: f.min(1.0),
+            fresnel: Fresnel::measured(spectrum),
+            ..Self::new(Color::new(1.0, 1.0, 1.0), roughness)
         }
     }
+    /// 按名字选择内置导体（gold、silver、copper、aluminum、chrome、iron、titanium）
+    pub fn from_name(name: &str, roughness: f64) -> Option<Self> {
+        ConductorSpectrum::from_name(name).map(|s| Self::new_measured(s, roughness))
+    }
+    /// 使用复折射率 eta + i·k 的导体，albedo 作为额外色调（默认白色）
+    pub fn new_conductor(eta: Color, k: Color, roughness: f64) -> Self {
+        Self {
+            fresnel: Fresnel::Conductor { eta, k },
+            ..Self::new(Color::new(1.0, 1.0, 1.0), roughness)
+        }
+    }
+
+    /// 在表面覆盖一层薄膜
+    pub fn with_thin_film(mut self, film: ThinFilm) -> Self {
+        self.thin_film = Some(film);
+        self
+    }
+
+    fn fresnel(&self, cos_theta: f64, albedo: Color, r_in: &Ray, rec: &HitRecord) -> Color {
+        // 有薄膜时按波长计算干涉反射率，Schlick 模式下不再额外乘色调
+        if let Some(film) = &self.thin_film {
+            let tint = match self.fresnel {
+                Fresnel::Schlick => Color::new(1.0, 1.0, 1.0),
+                _ => albedo,
+            };
+            let substrate = |lambda| self.substrate(albedo, lambda);
+            return tint * film.reflectance(rec, cos_theta, 1.0, substrate, r_in.wavelength());
+        }
+        match self.fresnel {
+            Fresnel::Schlick => microfacet::schlick(albedo, cos_theta),
+            Fresnel::Conductor { eta, k } => {
+                albedo * microfacet::fresnel_conductor(cos_theta, eta, k)
+            }
+            Fresnel::Measured { spectrum, eta, k } => match r_in.wavelength() {
+                Some(lambda) => {
+                    let (eta, k) = spectrum.eta_k(lambda);
+                    albedo * microfacet::fresnel_conductor_scalar(cos_theta, eta, k)
+                }
+                None => albedo * microfacet::fresnel_conductor(cos_theta, eta, k),
+            },
+        }
+    }
+
+    // 波长 lambda 处基底的复折射率；Schlick 模式下把 F0 换算为复折射率（边缘色调取 F0）
+    fn substrate(&self, albedo: Color, lambda: f64) -> Complex {
+        let (eta, k) = match self.fresnel {
+            Fresnel::Measured { spectrum, .. } => {
+                let (eta, k) = spectrum.eta_k(lambda);
+                return Complex::new(eta, k);
+            }
+            Fresnel::Conductor { eta, k } => (eta, k),
+            Fresnel::Schlick => {
+                let mut eta = Color::default();
+                let mut k = Color::default();
+                for c in 0..3 {
+                    let n = thin_film::artist_friendly_conductor(albedo[c], albedo[c]);
+                    eta[c] = n.re;
+                    k[c] = n.im;
+                }
+                (eta, k)
+            }
+        };
+        Complex::new(
+            spectrum::rgb_to_spectrum(eta, lambda),
+            spectrum::rgb_to_spectrum(k, lambda).max(0.0),
+        )
+    }
 }
 
 impl Material for Metal {
@@ -95,26 +340,92 @@ impl Material for Metal {
         attenuation: &mut Color, //衰减
         scattered: &mut Ray,     //散射光线
     ) -> bool {
-        let mut reflected = vec3::reflect(r_in.direction(), rec.normal);
-        reflected = Vec3::unit_vector(&reflected) + self.fuzz * vec3::random_in_unit_sphere();
-        *scattered = Ray::new(rec.p, reflected, r_in.time());
-        *attenuation = self.albedo.value(rec.u, rec.v, &rec.p);
-        vec3::dot(scattered.direction(), rec.normal) > 0.0
+        let albedo = self.albedo.value(rec.u, rec.v, &rec.p);
+        let roughness = self.roughness.value(rec.u, rec.v, &rec.p).x;
+        let distrib = TrowbridgeReitz::from_roughness(roughness);
+
+        let onb = Onb::new(rec.normal);
+        let wo = onb.to_local(-vec3::unit_vector(r_in.direction()));
+        if wo.z <= 0.0 {
+            return false;
+        }
+
+        if distrib.effectively_smooth() {
+            // 理想镜面反射
+            let wi = Vec3::new(-wo.x, -wo.y, wo.z);
+            *scattered = Ray::new(rec.p, onb.transform(wi), r_in.time());
+            *attenuation = self.fresnel(wo.z, albedo, r_in, rec);
+            return true;
+        }
+
+        // 按可见法线分布采样，权重 f·cos/pdf = F · G2 / G1(wo)
+        let wm = distrib.sample_wm(wo);
+        let wi = vec3::reflect(-wo, wm);
+        if wi.z <= 0.0 {
+            return false;
+        }
+        *scattered = Ray::new(rec.p, onb.transform(wi), r_in.time());
+        *attenuation = self.fresnel(vec3::dot(wo, wm), albedo, r_in, rec)
+            * (distrib.g(wo, wi) / distrib.g1(wo));
+        true
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        let roughness = self.roughness.value(rec.u, rec.v, &rec.p).x;
+        let distrib = TrowbridgeReitz::from_roughness(roughness);
+        if distrib.effectively_smooth() {
+            return None;
+        }
+        let onb = Onb::new(rec.normal);
+        let wo = onb.to_local(-vec3::unit_vector(r_in.direction()));
+        let wi = onb.to_local(vec3::unit_vector(direction));
+        let Some((wm, ggx)) = ggx_reflection(&distrib, wo, wi) else {
+            return Some(Color::default());
+        };
+        let albedo = self.albedo.value(rec.u, rec.v, &rec.p);
+        Some(ggx * self.fresnel(vec3::dot(wo, wm), albedo, r_in, rec))
+    }
+
+    // 实测光谱和薄膜的反射率都随波长变化
+    fn dispersive(&self) -> bool {
+        self.thin_film.is_some() || matches!(self.fresnel, Fresnel::Measured { .. })
     }
 }
 
 // Dielectric  透明电介质材质（折射）
 #[derive(Debug, Clone, Default)]
 pub struct Dielectric {
-    pub ir: f64, // 折射指数 (Index of Refraction)
+    pub ir: f64,                        // 折射指数 (Index of Refraction)
+    pub absorption: Color,              // 内部吸收系数 σa（每单位距离），按 Beer–Lambert 衰减
+    pub dispersion: Option<Dispersion>, // 光谱模式下按波长计算折射率
+    pub thin_film: Option<ThinFilm>,    // 表面薄膜（肥皂泡、镀膜），产生虹彩
 }
 
 impl Dielectric {
     pub fn new(index_of_refraction: f64) -> Self {
         Self {
             ir: index_of_refraction,
+            absorption: Color::default(),
+            dispersion: None,
+            thin_film: None,
         }
     }
+    /// 设置色散模型，ir 同时更新为 d 线处的折射率，供 RGB 模式使用
+    pub fn with_dispersion(mut self, dispersion: Dispersion) -> Self {
+        self.ir = dispersion.ior_d();
+        self.dispersion = Some(dispersion);
+        self
+    }
+    /// 设置内部吸收：光线在介质内走过 distance 后剩余的颜色为 color
+    pub fn with_absorption(mut self, color: Color, distance: f64) -> Self {
+        self.absorption = absorption_from_color(color, distance);
+        self
+    }
+    /// 在表面覆盖一层薄膜。肥皂泡可用 ir = 1.0 的 Dielectric 加水膜表示
+    pub fn with_thin_film(mut self, film: ThinFilm) -> Self {
+        self.thin_film = Some(film);
+        self
+    }
     pub fn reflectance(cosine: f64, refraction_idx: f64) -> f64 {
         let r0 = (1.0 - refraction_idx) / (1.0 + refraction_idx);
         let r0 = r0 * r0;
@@ -130,18 +441,47 @@ impl Material for Dielectric {
         attenuation: &mut Color,
         scattered: &mut Ray,
     ) -> bool {
-        *attenuation = Color::new(1.0, 1.0, 1.0); // 完全透射，无颜色衰减
+        // 表面本身无颜色，只有从内部射出时计入体内吸收
+        *attenuation = beer_lambert(self.absorption, r_in, rec);
 
-        let refraction_ratio = if rec.front_face {
-            1.0 / self.ir
-        } else {
-            self.ir
+        let ir = match (self.dispersion, r_in.wavelength()) {
+            (Some(dispersion), Some(lambda)) => dispersion.ior(lambda),
+            _ => self.ir,
         };
+        let refraction_ratio = if rec.front_face { 1.0 / ir } else { ir };
 
         let unit_direction = vec3::unit_vector(r_in.direction());
         let cos_theta = vec3::dot(-unit_direction, rec.normal).min(1.0);
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
         let cannot_theta = refraction_ratio * sin_theta > 1.0;
+
+        // 薄膜：反射率随波长变化，按平均反射率选择反射或折射，权重补偿颜色
+        if let Some(film) = &self.thin_film
+            && !cannot_theta
+        {
+            let (n_incident, n_substrate) = if rec.front_face { (1.0, ir) } else { (ir, 1.0) };
+            let f = film.reflectance(
+                rec,
+                cos_theta,
+                n_incident,
+                |_| Complex::real(n_substrate),
+                r_in.wavelength(),
+            );
+            let prob = ((f.x + f.y + f.z) / 3.0).clamp(1e-4, 1.0 - 1e-4);
+            let white = Color::new(1.0, 1.0, 1.0);
+            let (dirc, weight) = if rtweekend::random_double() < prob {
+                (vec3::reflect(unit_direction, rec.normal), f / prob)
+            } else {
+                (
+                    vec3::refract(unit_direction, rec.normal, refraction_ratio),
+                    (white - f) / (1.0 - prob),
+                )
+            };
+            *scattered = Ray::new(rec.p, dirc, r_in.time());
+            *attenuation = *attenuation * weight;
+            return true;
+        }
+
         let dirc = if cannot_theta
             || Dielectric::reflectance(cos_theta, refraction_ratio) > rtweekend::random_double()
         {
@@ -152,21 +492,1007 @@ impl Material for Dielectric {
         *scattered = Ray::new(rec.p, dirc, r_in.time());
         true
     }
+
+    fn dispersive(&self) -> bool {
+        self.dispersion.is_some() || self.thin_film.is_some()
+    }
+}
+
+// RoughDielectric  GGX 微表面粗糙电介质（磨砂玻璃），Walter et al. 2007
+pub struct RoughDielectric {
+    pub ir: f64,
+    pub roughness: Arc<dyn Texture + Send + Sync>, // 感知粗糙度（取 x 通道）
+    pub tint: Arc<dyn Texture + Send + Sync>,      // 从外部折射进入时的表面色调
+    pub absorption: Color,                         // 内部吸收系数 σa（每单位距离）
+}
+
+impl RoughDielectric {
+    pub fn new(index_of_refraction: f64, roughness: f64) -> Self {
+        let r = roughness.clamp(0.0, 1.0);
+        Self::new_from_textures(
+            index_of_refraction,
+            Arc::new(SolidColor::new(Color::new(r, r, r))),
+            Arc::new(SolidColor::new(Color::new(1.0, 1.0, 1.0))),
+        )
+    }
+    pub fn new_from_textures(
+        index_of_refraction: f64,
+        roughness: Arc<dyn Texture + Send + Sync>,
+        tint: Arc<dyn Texture + Send + Sync>,
+    ) -> Self {
+        Self {
+            ir: index_of_refraction,
+            roughness,
+            tint,
+            absorption: Color::default(),
+        }
+    }
+    pub fn with_tint(mut self, tint: Color) -> Self {
+        self.tint = Arc::new(SolidColor::new(tint));
+        self
+    }
+    /// 设置内部吸收：光线在介质内走过 distance 后剩余的颜色为 color
+    pub fn with_absorption(mut self, color: Color, distance: f64) -> Self {
+        self.absorption = absorption_from_color(color, distance);
+        self
+    }
+}
+
+impl Material for RoughDielectric {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let roughness = self.roughness.value(rec.u, rec.v, &rec.p).x;
+        let distrib = TrowbridgeReitz::from_roughness(roughness);
+        let tint = self.tint.value(rec.u, rec.v, &rec.p);
+        let absorbed = beer_lambert(self.absorption, r_in, rec);
+
+        // eta 为透射侧与入射侧折射率之比
+        let eta = if rec.front_face {
+            self.ir
+        } else {
+            1.0 / self.ir
+        };
+        let onb = Onb::new(rec.normal);
+        let wo = onb.to_local(-vec3::unit_vector(r_in.direction()));
+        if wo.z <= 0.0 {
+            return false;
+        }
+
+        let wm = if distrib.effectively_smooth() {
+            Vec3::new(0.0, 0.0, 1.0)
+        } else {
+            distrib.sample_wm(wo)
+        };
+        let cos_o = vec3::dot(wo, wm);
+        let f = microfacet::fresnel_dielectric(cos_o, eta);
+
+        // 按菲涅尔概率在反射和折射之间选择，F 与选择概率相消
+        let (wi, surface) = if rtweekend::random_double() < f {
+            let wi = vec3::reflect(-wo, wm);
+            if wi.z <= 0.0 {
+                return false;
+            }
+            (wi, Color::new(1.0, 1.0, 1.0))
+        } else {
+            let wi = vec3::refract(-wo, wm, 1.0 / eta);
+            if wi.z >= 0.0 {
+                return false;
+            }
+            // 只在进入时着色，穿过闭合物体的光线只计一次色调
+            if rec.front_face {
+                (wi, tint)
+            } else {
+                (wi, Color::new(1.0, 1.0, 1.0))
+            }
+        };
+
+        let shadowing = if distrib.effectively_smooth() {
+            1.0
+        } else {
+            distrib.g(wo, wi) / distrib.g1(wo)
+        };
+        *scattered = Ray::new(rec.p, onb.transform(wi), r_in.time());
+        *attenuation = absorbed * surface * shadowing;
+        true
+    }
+}
+
+// Principled  迪士尼风格的统一 PBR 材质，参数与 glTF metallic-roughness 模型一一对应。
+// 各层按概率随机选择：清漆 → 金属 → 电介质镜面 → 透射 → 漫反射 + 光泽（sheen），
+// 选择概率与各层的权重相消，因此每次散射只需采样一个波瓣
+pub struct Principled {
+    pub base_color: Arc<dyn Texture + Send + Sync>,
+    pub metallic: Arc<dyn Texture + Send + Sync>, // 取 x 通道
+    pub roughness: Arc<dyn Texture + Send + Sync>, // 感知粗糙度（取 x 通道）
+    pub specular: Arc<dyn Texture + Send + Sync>, // 电介质镜面强度与颜色，F0 = ((ior-1)/(ior+1))² · specular
+    pub clearcoat: Arc<dyn Texture + Send + Sync>, // 清漆强度（取 x 通道），清漆层 F0 = 0.04
+    pub clearcoat_roughness: Arc<dyn Texture + Send + Sync>,
+    pub sheen: Arc<dyn Texture + Send + Sync>, // 光泽颜色，黑色表示没有光泽
+    pub transmission: Arc<dyn Texture + Send + Sync>, // 电介质部分中透射所占比例（取 x 通道）
+    pub emission: Arc<dyn Texture + Send + Sync>,
+    pub two_sided_emission: bool, // 背面是否也发光（glTF 的 doubleSided）
+    pub ior: f64,
+}
+
+impl Principled {
+    const CLEARCOAT_F0: f64 = 0.04;
+
+    pub fn new(base_color: Color, metallic: f64, roughness: f64) -> Self {
+        Self {
+            metallic: gray_texture(metallic),
+            roughness: gray_texture(roughness),
+            ..Self::new_from_texture(Arc::new(SolidColor::new(base_color)))
+        }
+    }
+    /// 以 glTF 的默认值（金属度 0、粗糙度 0.5、ior 1.5）创建，其余层均关闭
+    pub fn new_from_texture(base_color: Arc<dyn Texture + Send + Sync>) -> Self {
+        Self {
+            base_color,
+            metallic: gray_texture(0.0),
+            roughness: gray_texture(0.5),
+            specular: gray_texture(1.0),
+            clearcoat: gray_texture(0.0),
+            clearcoat_roughness: gray_texture(0.0),
+            sheen: gray_texture(0.0),
+            transmission: gray_texture(0.0),
+            emission: gray_texture(0.0),
+            two_sided_emission: false,
+            ior: 1.5,
+        }
+    }
+    pub fn with_clearcoat(mut self, amount: f64, roughness: f64) -> Self {
+        self.clearcoat = gray_texture(amount);
+        self.clearcoat_roughness = gray_texture(roughness);
+        self
+    }
+    pub fn with_sheen(mut self, sheen: Color) -> Self {
+        self.sheen = Arc::new(SolidColor::new(sheen));
+        self
+    }
+    pub fn with_transmission(mut self, transmission: f64, ior: f64) -> Self {
+        self.transmission = gray_texture(transmission);
+        self.ior = ior;
+        self
+    }
+    pub fn with_emission(mut self, emission: Color) -> Self {
+        self.emission = Arc::new(SolidColor::new(emission));
+        self
+    }
+}
+
+impl Material for Principled {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let (u, v, p) = (rec.u, rec.v, &rec.p);
+        let white = Color::new(1.0, 1.0, 1.0);
+
+        let onb = Onb::new(rec.normal);
+        let wo = onb.to_local(-vec3::unit_vector(r_in.direction()));
+        if wo.z <= 0.0 {
+            return false;
+        }
+
+        // 反射类波瓣：wi 为 wo 关于微表面法线的镜像，权重 F · G2 / G1
+        let reflect =
+            |distrib: &TrowbridgeReitz, wm: Vec3, fresnel: Color| -> Option<(Vec3, Color)> {
+                let wi = vec3::reflect(-wo, wm);
+                (wi.z > 0.0).then(|| (wi, fresnel * smith_shadowing(distrib, wo, wi)))
+            };
+
+        // 各层按宏观法线方向的菲涅尔选择（只依赖 wo，与 eval 一致），
+        // 选中的镜面波瓣再按微表面法线的菲涅尔与选择概率之比加权
+        // 清漆层
+        let clearcoat = self.clearcoat.value(u, v, p).x.clamp(0.0, 1.0);
+        let lobe = 'lobe: {
+            if clearcoat > 0.0 {
+                let prob = clearcoat * schlick_scalar(Self::CLEARCOAT_F0, wo.z);
+                if rtweekend::random_double() < prob {
+                    let coat =
+                        TrowbridgeReitz::from_roughness(self.clearcoat_roughness.value(u, v, p).x);
+                    let wm = sample_microfacet(&coat, wo);
+                    let f = clearcoat * schlick_scalar(Self::CLEARCOAT_F0, vec3::dot(wo, wm));
+                    break 'lobe reflect(&coat, wm, white * (f / prob));
+                }
+            }
+
+            let base = self.base_color.value(u, v, p);
+            let distrib = TrowbridgeReitz::from_roughness(self.roughness.value(u, v, p).x);
+            let wm = sample_microfacet(&distrib, wo);
+            let cos_o = vec3::dot(wo, wm);
+
+            // 金属：以 base_color 为 F0 的导体
+            let metallic = self.metallic.value(u, v, p).x.clamp(0.0, 1.0);
+            if rtweekend::random_double() < metallic {
+                break 'lobe reflect(&distrib, wm, microfacet::schlick(base, cos_o));
+            }
+
+            // 电介质镜面：按平均菲涅尔反射率选择，F / prob 补偿有色镜面
+            let r0 = ((self.ior - 1.0) / (self.ior + 1.0)).powi(2);
+            let f0 = r0 * self.specular.value(u, v, p);
+            let f0 = Color::new(f0.x.min(1.0), f0.y.min(1.0), f0.z.min(1.0));
+            let f = microfacet::schlick(f0, wo.z);
+            let prob = ((f.x + f.y + f.z) / 3.0).clamp(1e-4, 1.0 - 1e-4);
+            if rtweekend::random_double() < prob {
+                break 'lobe reflect(&distrib, wm, microfacet::schlick(f0, cos_o) / prob);
+            }
+            let remain = (white - f) / (1.0 - prob);
+
+            // 透射：穿过微表面折射，被 base_color 着色；全反射时改为反射
+            let transmission = self.transmission.value(u, v, p).x.clamp(0.0, 1.0);
+            if rtweekend::random_double() < transmission {
+                let eta = if rec.front_face {
+                    self.ior
+                } else {
+                    1.0 / self.ior
+                };
+                if microfacet::fresnel_dielectric(cos_o, eta) >= 1.0 {
+                    break 'lobe reflect(&distrib, wm, remain);
+                }
+                let wi = vec3::refract(-wo, wm, 1.0 / eta);
+                break 'lobe (wi.z < 0.0)
+                    .then(|| (wi, remain * base * smith_shadowing(&distrib, wo, wi)));
+            }
+
+            // 漫反射 + 光泽：余弦采样，sheen 按 (1 - cosθd)^5 在掠射角增强
+            let mut dir = rec.normal + vec3::random_unit_vector();
+            if dir.near_zero() {
+                dir = rec.normal;
+            }
+            let wi = onb.to_local(vec3::unit_vector(dir));
+            let cos_d = vec3::dot(wi, vec3::unit_vector(wi + wo)).clamp(0.0, 1.0);
+            let sheen = self.sheen.value(u, v, p) * (1.0 - cos_d).powi(5);
+            Some((wi, remain * (base + sheen)))
+        };
+
+        match lobe {
+            Some((wi, weight)) => {
+                *scattered = Ray::new(rec.p, onb.transform(wi), r_in.time());
+                *attenuation = weight;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // scatter 各层的期望之和；任一层理想光滑时含有镜面成分，无法按方向求值
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        let (u, v, p) = (rec.u, rec.v, &rec.p);
+        let white = Color::new(1.0, 1.0, 1.0);
+        let clearcoat = self.clearcoat.value(u, v, p).x.clamp(0.0, 1.0);
+        let coat = TrowbridgeReitz::from_roughness(self.clearcoat_roughness.value(u, v, p).x);
+        let distrib = TrowbridgeReitz::from_roughness(self.roughness.value(u, v, p).x);
+        if distrib.effectively_smooth() || (clearcoat > 0.0 && coat.effectively_smooth()) {
+            return None;
+        }
+
+        let onb = Onb::new(rec.normal);
+        let wo = onb.to_local(-vec3::unit_vector(r_in.direction()));
+        let wi = onb.to_local(vec3::unit_vector(direction));
+        if wo.z <= 0.0 {
+            return Some(Color::default());
+        }
+        let base = self.base_color.value(u, v, p);
+        let metallic = self.metallic.value(u, v, p).x.clamp(0.0, 1.0);
+        let transmission = self.transmission.value(u, v, p).x.clamp(0.0, 1.0);
+        let r0 = ((self.ior - 1.0) / (self.ior + 1.0)).powi(2);
+        let f0 = r0 * self.specular.value(u, v, p);
+        let f0 = Color::new(f0.x.min(1.0), f0.y.min(1.0), f0.z.min(1.0));
+        let coat_remain = 1.0 - clearcoat * schlick_scalar(Self::CLEARCOAT_F0, wo.z);
+        let remain = white - microfacet::schlick(f0, wo.z);
+        let dielectric = (1.0 - metallic) * coat_remain;
+
+        if wi.z < 0.0 {
+            // 透射：粗糙电介质 BTDF（不含菲涅尔，由 remain 给出），与 scatter 一样不按 η² 缩放辐亮度
+            let eta = if rec.front_face {
+                self.ior
+            } else {
+                1.0 / self.ior
+            };
+            let btdf = ggx_transmission(&distrib, wo, wi, eta);
+            return Some(dielectric * transmission * btdf * remain * base);
+        }
+
+        let mut f = Color::default();
+        if let Some((wm, ggx)) = ggx_reflection(&coat, wo, wi).filter(|_| clearcoat > 0.0) {
+            f += clearcoat * schlick_scalar(Self::CLEARCOAT_F0, vec3::dot(wo, wm)) * ggx * white;
+        }
+        if let Some((wm, ggx)) = ggx_reflection(&distrib, wo, wi) {
+            let cos_m = vec3::dot(wo, wm);
+            f += coat_remain * metallic * ggx * microfacet::schlick(base, cos_m);
+            f += dielectric * ggx * microfacet::schlick(f0, cos_m);
+        }
+        let cos_d = vec3::dot(wi, vec3::unit_vector(wi + wo)).clamp(0.0, 1.0);
+        let sheen = self.sheen.value(u, v, p) * (1.0 - cos_d).powi(5);
+        f += dielectric * (1.0 - transmission) * wi.z / rtweekend::PI * remain * (base + sheen);
+        Some(f)
+    }
+
+    fn emitted(&self, _r_in: &Ray, rec: &HitRecord) -> Color {
+        if !rec.front_face && !self.two_sided_emission {
+            return Color::default();
+        }
+        self.emission.value(rec.u, rec.v, &rec.p)
+    }
+}
+
+fn gray_texture(value: f64) -> Arc<dyn Texture + Send + Sync> {
+    Arc::new(SolidColor::new(Color::new(value, value, value)))
+}
+
+fn schlick_scalar(f0: f64, cos_theta: f64) -> f64 {
+    f0 + (1.0 - f0) * (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5)
+}
+
+// 采样微表面法线，理想光滑时直接取宏观法线
+fn sample_microfacet(distrib: &TrowbridgeReitz, wo: Vec3) -> Vec3 {
+    if distrib.effectively_smooth() {
+        Vec3::new(0.0, 0.0, 1.0)
+    } else {
+        distrib.sample_wm(wo)
+    }
+}
+
+// VNDF 采样下的遮蔽权重 G2 / G1(wo)，理想光滑时为 1
+fn smith_shadowing(distrib: &TrowbridgeReitz, wo: Vec3, wi: Vec3) -> f64 {
+    if distrib.effectively_smooth() {
+        1.0
+    } else {
+        distrib.g(wo, wi) / distrib.g1(wo)
+    }
+}
+
+// GGX 反射波瓣的 D · G2 / (4 cosθo)（即不含菲涅尔的 BSDF · cosθi）与半程向量，
+// wo、wi 不在上半球时返回 None
+fn ggx_reflection(distrib: &TrowbridgeReitz, wo: Vec3, wi: Vec3) -> Option<(Vec3, f64)> {
+    if wo.z <= 0.0 || wi.z <= 0.0 {
+        return None;
+    }
+    let wm = vec3::unit_vector(wo + wi);
+    Some((wm, distrib.d(wm) * distrib.g(wo, wi) / (4.0 * wo.z)))
+}
+
+// GGX 透射波瓣（Walter 2007）不含菲涅尔的 BTDF · |cosθi|，eta 为透射侧与入射侧折射率之比
+fn ggx_transmission(distrib: &TrowbridgeReitz, wo: Vec3, wi: Vec3, eta: f64) -> f64 {
+    let mut wm = wo + eta * wi;
+    if wo.z <= 0.0 || wi.z >= 0.0 || wm.near_zero() {
+        return 0.0;
+    }
+    wm = vec3::unit_vector(wm);
+    if wm.z < 0.0 {
+        wm = -wm;
+    }
+    let (cos_o, cos_i) = (vec3::dot(wo, wm), vec3::dot(wi, wm));
+    if cos_o <= 0.0 || cos_i >= 0.0 {
+        return 0.0;
+    }
+    let denom = cos_o + eta * cos_i;
+    distrib.d(wm) * distrib.g(wo, wi) * cos_o * eta * eta * cos_i.abs() / (wo.z * denom * denom)
+}
+
+// Coated  任意基底材质上覆盖一层电介质涂层（车漆、清漆木材）。
+// 涂层与基底之间按随机游走选择：在界面按菲涅尔概率反射或折射进入涂层，
+// 进入后交给基底散射，返回的光线可能再被界面反射回基底，穿过涂层时按厚度吸收
+pub struct Coated {
+    pub base: Arc<dyn Material + Send + Sync>,
+    pub ior: f64,
+    pub roughness: Arc<dyn Texture + Send + Sync>, // 涂层表面的感知粗糙度（取 x 通道）
+    pub thickness: f64,
+    pub absorption: Color, // 涂层吸收系数 σa（每单位厚度）
+}
+
+impl Coated {
+    // 涂层与基底之间最多往返的次数
+    const MAX_BOUNCES: usize = 16;
+
+    pub fn new(base: Arc<dyn Material + Send + Sync>, ior: f64, roughness: f64) -> Self {
+        let r = roughness.clamp(0.0, 1.0);
+        Self::new_from_texture(base, ior, Arc::new(SolidColor::new(Color::new(r, r, r))))
+    }
+    pub fn new_from_texture(
+        base: Arc<dyn Material + Send + Sync>,
+        ior: f64,
+        roughness: Arc<dyn Texture + Send + Sync>,
+    ) -> Self {
+        Self {
+            base,
+            ior,
+            roughness,
+            thickness: 1.0,
+            absorption: Color::default(),
+        }
+    }
+    /// 设置涂层厚度与颜色：光线垂直穿过整层涂层后剩余的颜色为 color
+    pub fn with_absorption(mut self, color: Color, thickness: f64) -> Self {
+        self.thickness = thickness;
+        self.absorption = absorption_from_color(color, thickness);
+        self
+    }
+
+    // 在涂层内沿局部方向 w 穿过一次的透过率
+    fn transmittance(&self, w: Vec3) -> Color {
+        if self.absorption.near_zero() {
+            return Color::new(1.0, 1.0, 1.0);
+        }
+        let dist = self.thickness / w.z.abs().max(1e-4);
+        Color::new(
+            (-self.absorption.x * dist).exp(),
+            (-self.absorption.y * dist).exp(),
+            (-self.absorption.z * dist).exp(),
+        )
+    }
+}
+
+impl Material for Coated {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let roughness = self.roughness.value(rec.u, rec.v, &rec.p).x;
+        let distrib = TrowbridgeReitz::from_roughness(roughness);
+        let onb = Onb::new(rec.normal);
+        let wo = onb.to_local(-vec3::unit_vector(r_in.direction()));
+        if wo.z <= 0.0 {
+            return false;
+        }
+
+        // 涂层上表面：按菲涅尔概率直接反射
+        let wm = sample_microfacet(&distrib, wo);
+        let cos_o = vec3::dot(wo, wm);
+        if rtweekend::random_double() < microfacet::fresnel_dielectric(cos_o, self.ior) {
+            let wi = vec3::reflect(-wo, wm);
+            if wi.z <= 0.0 {
+                return false;
+            }
+            *scattered = Ray::new(rec.p, onb.transform(wi), r_in.time());
+            *attenuation = Color::new(1.0, 1.0, 1.0) * smith_shadowing(&distrib, wo, wi);
+            return true;
+        }
+
+        // 折射进入涂层
+        let mut down = vec3::refract(-wo, wm, 1.0 / self.ior);
+        if down.z >= 0.0 {
+            return false;
+        }
+        let mut throughput = Color::new(1.0, 1.0, 1.0) * smith_shadowing(&distrib, wo, down);
+
+        for _ in 0..Self::MAX_BOUNCES {
+            throughput = throughput * self.transmittance(down);
+
+            // 基底散射，光线方向为涂层内的折射方向
+            let base_in = Ray::new(rec.p, onb.transform(down), r_in.time())
+                .with_wavelength(r_in.wavelength());
+            let mut base_attenuation = Color::default();
+            let mut base_out = Ray::default();
+            if !self
+                .base
+                .scatter(&base_in, rec, &mut base_attenuation, &mut base_out)
+            {
+                return false;
+            }
+            throughput = throughput * base_attenuation;
+
+            let up = onb.to_local(vec3::unit_vector(base_out.direction()));
+            if up.z <= 0.0 {
+                // 基底本身透射（例如玻璃），光线继续进入物体内部
+                *scattered = base_out;
+                *attenuation = throughput;
+                return true;
+            }
+            throughput = throughput * self.transmittance(up);
+
+            // 涂层下表面：从内部射出，或被（全）反射回基底
+            let wm = sample_microfacet(&distrib, up);
+            if rtweekend::random_double()
+                < microfacet::fresnel_dielectric(vec3::dot(up, wm), 1.0 / self.ior)
+            {
+                down = vec3::reflect(up, wm);
+                if down.z >= 0.0 {
+                    return false;
+                }
+                continue;
+            }
+            let wi = vec3::refract(up, -wm, self.ior);
+            if wi.z <= 0.0 {
+                return false;
+            }
+            *scattered = Ray::new(rec.p, onb.transform(wi), r_in.time());
+            *attenuation = throughput;
+            return true;
+        }
+        false
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        self.base.emitted(r_in, rec)
+    }
+
+    fn dispersive(&self) -> bool {
+        self.base.dispersive()
+    }
+}
+
+// Subsurface  光滑电介质边界包裹的散射介质（大理石、牛奶、玉石），内部做体积随机游走。
+// 不需要访问几何体：从内部击中边界时（!front_face），r_in 的起点必然在介质内，
+// 先按自由程采样散射距离，未到达边界则在途中散射，否则在边界上按菲涅尔反射或折射出去。
+// RGB 模式下各通道系数不同，按均匀选择通道的单样本 MIS 采样距离；
+// 光谱模式下按 hero 波长上采样系数，只保留 hero 波长游走。
+// 每次内部散射消耗一次路径深度，散射系数较大时需要相应增大 max_depth
+pub struct Subsurface {
+    pub ior: f64,
+    pub sigma_a: Color, // 吸收系数（每单位距离）
+    pub sigma_s: Color, // 散射系数（每单位距离）
+    pub phase: HenyeyGreenstein,
+}
+
+impl Subsurface {
+    pub fn new(ior: f64, sigma_a: Color, sigma_s: Color) -> Self {
+        Self {
+            ior,
+            sigma_a,
+            sigma_s,
+            phase: HenyeyGreenstein::new(0.0),
+        }
+    }
+
+    /// 由多次散射后的表观颜色 albedo 和平均自由程 mean_free_path 推出系数。
+    /// 单次散射反照率按 Christensen–Burley 的拟合从表观反照率反推
+    pub fn from_albedo(ior: f64, albedo: Color, mean_free_path: Color) -> Self {
+        let mut sigma_a = Color::default();
+        let mut sigma_s = Color::default();
+        for c in 0..3 {
+            let a = albedo[c].clamp(0.0, 0.999);
+            let single = 1.0
+                - (4.09712 + 4.20863 * a - (9.59217 + 41.6808 * a + 17.7126 * a * a).sqrt())
+                    .powi(2);
+            let sigma_t = 1.0 / mean_free_path[c].max(1e-6);
+            sigma_s[c] = single * sigma_t;
+            sigma_a[c] = (1.0 - single) * sigma_t;
+        }
+        Self::new(ior, sigma_a, sigma_s)
+    }
+
+    /// 设置相函数的各向异性参数 g
+    pub fn with_anisotropy(mut self, g: f64) -> Self {
+        self.phase = HenyeyGreenstein::new(g);
+        self
+    }
+
+    // 当前光线对应的 (σs, σt)，光谱模式下三个通道取相同的 hero 波长值
+    fn coefficients(&self, r_in: &Ray) -> (Color, Color) {
+        match r_in.wavelength() {
+            Some(lambda) => {
+                let s = spectrum::rgb_to_spectrum(self.sigma_s, lambda).max(0.0);
+                let a = spectrum::rgb_to_spectrum(self.sigma_a, lambda).max(0.0);
+                (Color::new(s, s, s), Color::new(s + a, s + a, s + a))
+            }
+            None => (self.sigma_s, self.sigma_s + self.sigma_a),
+        }
+    }
+}
+
+impl Material for Subsurface {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let unit_direction = vec3::unit_vector(r_in.direction());
+        *attenuation = Color::new(1.0, 1.0, 1.0);
+
+        if !rec.front_face {
+            let (sigma_s, sigma_t) = self.coefficients(r_in);
+            let dist = rec.t * r_in.direction().length();
+            let channel = rtweekend::random_int_range(0, 2) as usize;
+            let d = if sigma_t[channel] > 0.0 {
+                -(1.0 - rtweekend::random_double()).ln() / sigma_t[channel]
+            } else {
+                f64::INFINITY
+            };
+
+            let transmittance = |d: f64| {
+                Color::new(
+                    (-sigma_t.x * d).exp(),
+                    (-sigma_t.y * d).exp(),
+                    (-sigma_t.z * d).exp(),
+                )
+            };
+            if d < dist {
+                // 介质内散射：pdf 为三个通道自由程密度的平均
+                let tr = transmittance(d);
+                let pdf = (sigma_t.x * tr.x + sigma_t.y * tr.y + sigma_t.z * tr.z) / 3.0;
+                let p = r_in.origin() + d * unit_direction;
+                *scattered = Ray::new(p, self.phase.sample(unit_direction), r_in.time());
+                *attenuation = sigma_s * tr / pdf;
+                return true;
+            }
+            // 穿过整段到达边界：概率为三个通道透过率的平均
+            let tr = transmittance(dist);
+            let prob = (tr.x + tr.y + tr.z) / 3.0;
+            if prob <= 0.0 {
+                return false;
+            }
+            *attenuation = tr / prob;
+        }
+
+        let refraction_ratio = if rec.front_face {
+            1.0 / self.ior
+        } else {
+            self.ior
+        };
+        let cos_theta = vec3::dot(-unit_direction, rec.normal).min(1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let dirc = if refraction_ratio * sin_theta > 1.0
+            || Dielectric::reflectance(cos_theta, refraction_ratio) > rtweekend::random_double()
+        {
+            vec3::reflect(unit_direction, rec.normal)
+        } else {
+            vec3::refract(unit_direction, rec.normal, refraction_ratio)
+        };
+        *scattered = Ray::new(rec.p, dirc, r_in.time());
+        true
+    }
+
+    // 光谱模式下的游走只对 hero 波长成立
+    fn dispersive(&self) -> bool {
+        true
+    }
+}
+
+// MixMaterial  按纹理（或常数）权重在两种材质之间混合，weight 取 x 通道：0 为 a，1 为 b。
+// 每次击中按权重随机选择其一散射，期望上等于两者 BSDF 的线性混合；自发光直接按权重混合
+pub struct MixMaterial {
+    pub a: Arc<dyn Material + Send + Sync>,
+    pub b: Arc<dyn Material + Send + Sync>,
+    pub weight: Arc<dyn Texture + Send + Sync>,
+}
+
+impl MixMaterial {
+    pub fn new(
+        a: Arc<dyn Material + Send + Sync>,
+        b: Arc<dyn Material + Send + Sync>,
+        weight: f64,
+    ) -> Self {
+        Self::new_from_texture(a, b, gray_texture(weight))
+    }
+    pub fn new_from_texture(
+        a: Arc<dyn Material + Send + Sync>,
+        b: Arc<dyn Material + Send + Sync>,
+        weight: Arc<dyn Texture + Send + Sync>,
+    ) -> Self {
+        Self { a, b, weight }
+    }
+
+    fn weight(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        self.weight.value(u, v, p).x.clamp(0.0, 1.0)
+    }
+}
+
+impl Material for MixMaterial {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        if rtweekend::random_double() < self.weight(rec.u, rec.v, &rec.p) {
+            self.b.scatter(r_in, rec, attenuation, scattered)
+        } else {
+            self.a.scatter(r_in, rec, attenuation, scattered)
+        }
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        let w = self.weight(rec.u, rec.v, &rec.p);
+        (1.0 - w) * self.a.emitted(r_in, rec) + w * self.b.emitted(r_in, rec)
+    }
+
+    // 两者都能按方向求值时才能混合，否则按镜面处理
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        let w = self.weight(rec.u, rec.v, &rec.p);
+        let a = self.a.eval(r_in, rec, direction)?;
+        let b = self.b.eval(r_in, rec, direction)?;
+        Some((1.0 - w) * a + w * b)
+    }
+
+    fn dispersive(&self) -> bool {
+        self.a.dispersive() || self.b.dispersive()
+    }
+}
+
+// NormalMapped  切线空间法线贴图（与 glTF normalTexture 相同的约定：RGB 映射到 [-1, 1]，
+// +x 沿 u、+y 沿 v、+z 沿法线），scale 缩放 xy 分量
+pub struct NormalMapped {
+    pub base: Arc<dyn Material + Send + Sync>,
+    pub normal_map: Arc<dyn Texture + Send + Sync>,
+    pub scale: f64,
+}
+
+impl NormalMapped {
+    pub fn new(
+        base: Arc<dyn Material + Send + Sync>,
+        normal_map: Arc<dyn Texture + Send + Sync>,
+        scale: f64,
+    ) -> Self {
+        Self {
+            base,
+            normal_map,
+            scale,
+        }
+    }
+}
+
+impl NormalMapped {
+    // 贴图给出的着色法线（指向物体外侧）
+    fn shading_normal(&self, rec: &HitRecord) -> Vec3 {
+        let c = self.normal_map.value(rec.u, rec.v, &rec.p);
+        let local = Vec3::new(
+            (2.0 * c.x - 1.0) * self.scale,
+            (2.0 * c.y - 1.0) * self.scale,
+            2.0 * c.z - 1.0,
+        );
+        let (t, b, n) = outward_frame(rec);
+        vec3::unit_vector(local.x * t + local.y * b + local.z * n)
+    }
+}
+
+impl Material for NormalMapped {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let n = self.shading_normal(rec);
+        scatter_with_shading_normal(self.base.as_ref(), n, r_in, rec, attenuation, scattered)
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        let n = self.shading_normal(rec);
+        eval_with_shading_normal(self.base.as_ref(), n, r_in, rec, direction)
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        self.base.emitted(r_in, rec)
+    }
+
+    fn dispersive(&self) -> bool {
+        self.base.dispersive()
+    }
+}
+
+// BumpMapped  高度凹凸贴图：height 取任意纹理的 x 通道，
+// 法线按高度在切线方向上的梯度倾斜，strength 为梯度的缩放
+pub struct BumpMapped {
+    pub base: Arc<dyn Material + Send + Sync>,
+    pub height: Arc<dyn Texture + Send + Sync>,
+    pub strength: f64,
+    pub delta: f64, // 有限差分步长（纹理空间，同时作为世界空间的偏移）
 }
 
+impl BumpMapped {
+    pub fn new(
+        base: Arc<dyn Material + Send + Sync>,
+        height: Arc<dyn Texture + Send + Sync>,
+        strength: f64,
+    ) -> Self {
+        Self {
+            base,
+            height,
+            strength,
+            delta: 5e-4,
+        }
+    }
+    /// 图片高度图的步长应不小于一个纹素（1 / 分辨率），否则梯度只在纹素边界上出现
+    pub fn with_delta(mut self, delta: f64) -> Self {
+        self.delta = delta;
+        self
+    }
+}
+
+impl BumpMapped {
+    // 按高度梯度倾斜后的着色法线（指向物体外侧）
+    fn shading_normal(&self, rec: &HitRecord) -> Vec3 {
+        let (t, b, n) = outward_frame(rec);
+        // 中心差分
+        let d = self.delta;
+        let h = |du: f64, dv: f64, offset: Vec3| {
+            self.height
+                .value(rec.u + du, rec.v + dv, &(rec.p + offset))
+                .x
+        };
+        let dh_du = (h(d, 0.0, d * t) - h(-d, 0.0, -d * t)) / (2.0 * d);
+        let dh_dv = (h(0.0, d, d * b) - h(0.0, -d, -d * b)) / (2.0 * d);
+        vec3::unit_vector(n - self.strength * (dh_du * t + dh_dv * b))
+    }
+}
+
+impl Material for BumpMapped {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        let n = self.shading_normal(rec);
+        scatter_with_shading_normal(self.base.as_ref(), n, r_in, rec, attenuation, scattered)
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        let n = self.shading_normal(rec);
+        eval_with_shading_normal(self.base.as_ref(), n, r_in, rec, direction)
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        self.base.emitted(r_in, rec)
+    }
+
+    fn dispersive(&self) -> bool {
+        self.base.dispersive()
+    }
+}
+
+// 指向物体外侧的切线坐标系 (T, B, N)，T 对 N 做正交化
+fn outward_frame(rec: &HitRecord) -> (Vec3, Vec3, Vec3) {
+    let n = if rec.front_face {
+        rec.normal
+    } else {
+        -rec.normal
+    };
+    let t = rec.tangent - vec3::dot(rec.tangent, n) * n;
+    if t.near_zero() {
+        let onb = Onb::new(n);
+        return (onb.u(), onb.v(), n);
+    }
+    let t = vec3::unit_vector(t);
+    let b = if vec3::dot(vec3::cross(n, t), rec.bitangent) < 0.0 {
+        -vec3::cross(n, t)
+    } else {
+        vec3::cross(n, t)
+    };
+    (t, b, n)
+}
+
+// 用扰动后的着色法线（指向物体外侧）调用基底材质。
+// 视线落在着色法线背面时退回几何法线；散射方向相对着色法线和几何法线
+// 位于不同侧时视为漏光，直接吸收
+fn scatter_with_shading_normal(
+    base: &dyn Material,
+    outward_shading_normal: Vec3,
+    r_in: &Ray,
+    rec: &HitRecord,
+    attenuation: &mut Color,
+    scattered: &mut Ray,
+) -> bool {
+    let shading = shading_record(outward_shading_normal, r_in, rec);
+    if !base.scatter(r_in, &shading, attenuation, scattered) {
+        return false;
+    }
+    let dir = scattered.direction();
+    vec3::dot(dir, shading.normal) * vec3::dot(dir, rec.geometric_normal) > 0.0
+}
+
+// 与 scatter_with_shading_normal 相同的约定下求值，漏光方向为 0
+fn eval_with_shading_normal(
+    base: &dyn Material,
+    outward_shading_normal: Vec3,
+    r_in: &Ray,
+    rec: &HitRecord,
+    direction: Vec3,
+) -> Option<Color> {
+    let shading = shading_record(outward_shading_normal, r_in, rec);
+    let f = base.eval(r_in, &shading, direction)?;
+    if vec3::dot(direction, shading.normal) * vec3::dot(direction, rec.geometric_normal) > 0.0 {
+        Some(f)
+    } else {
+        Some(Color::default())
+    }
+}
+
+fn shading_record(outward_shading_normal: Vec3, r_in: &Ray, rec: &HitRecord) -> HitRecord {
+    let mut shading = rec.clone();
+    shading.normal = if rec.front_face {
+        outward_shading_normal
+    } else {
+        -outward_shading_normal
+    };
+    if vec3::dot(r_in.direction(), shading.normal) >= 0.0 {
+        shading.normal = rec.geometric_normal;
+    }
+    shading
+}
+
+// 由目标颜色反推吸收系数：exp(-σa · distance) = color
+fn absorption_from_color(color: Color, distance: f64) -> Color {
+    let sigma = |c: f64| -(c.clamp(1e-6, 1.0)).ln() / distance;
+    Color::new(sigma(color.x), sigma(color.y), sigma(color.z))
+}
+
+// Beer–Lambert 透过率：光线从介质内部击中边界时（!front_face），
+// r_in 的起点在介质内，走过的距离即 t · |d|
+fn beer_lambert(absorption: Color, r_in: &Ray, rec: &HitRecord) -> Color {
+    if rec.front_face || absorption.near_zero() {
+        return Color::new(1.0, 1.0, 1.0);
+    }
+    let dist = rec.t * r_in.direction().length();
+    Color::new(
+        (-absorption.x * dist).exp(),
+        (-absorption.y * dist).exp(),
+        (-absorption.z * dist).exp(),
+    )
+}
+
+/// 朗伯发光面，默认只有正面（外法线一侧）发光
 pub struct DiffuseLight {
     emit: Arc<dyn Texture>,
+    two_sided: bool,
+    exponent: f64,             // 方向分布 cosⁿθ 的指数，0 为朗伯发光
+    power: Option<(f64, f64)>, // 总光通量（lm）与发光面积
 }
 
 impl DiffuseLight {
     pub fn new_from_texture(texture: Arc<dyn Texture>) -> Self {
-        Self { emit: texture }
+        Self {
+            emit: texture,
+            two_sided: false,
+            exponent: 0.0,
+            power: None,
+        }
     }
 
     pub fn new_from_color(c: Color) -> Self {
-        Self {
-            emit: Arc::new(SolidColor::new(c)),
-        }
+        Self::new_from_texture(Arc::new(SolidColor::new(c)))
+    }
+
+    /// 色温为 kelvin 的黑体光源，luminance 为亮度（cd/m²，配合 PhysicalExposure 使用）
+    pub fn new_from_temperature(kelvin: f64, luminance: f64) -> Self {
+        Self::new_from_color(luminance * spectrum::blackbody(kelvin))
+    }
+
+    /// 两面都发光
+    pub fn with_two_sided(mut self, two_sided: bool) -> Self {
+        self.two_sided = two_sided;
+        self
+    }
+
+    /// 类似聚光灯的方向分布：辐亮度按 cosⁿθ 随偏离法线的角度衰减，法线方向的亮度不变。
+    /// 未用 with_power 给定光通量时，总光通量变为朗伯发光的 2 / (n + 2)
+    pub fn with_cosine_power(mut self, exponent: f64) -> Self {
+        self.exponent = exponent.max(0.0);
+        self
+    }
+
+    /// 按总光通量（lm）给出亮度，此时纹理只作为亮度为 1 的光色（例如 spectrum::blackbody 的结果）。
+    /// area 为单面的发光面积；两面发光时每面各占一半，cosⁿθ 分布时保持总光通量不变
+    pub fn with_power(mut self, power: f64, area: f64) -> Self {
+        self.power = Some((power, area));
+        self
+    }
+
+    // 光通量对应的亮度缩放：每面 Φ = L·A·2π / (n + 2)
+    fn power_scale(&self) -> f64 {
+        let Some((power, area)) = self.power else {
+            return 1.0;
+        };
+        let sides = if self.two_sided { 2.0 } else { 1.0 };
+        power * (self.exponent + 2.0) / (2.0 * rtweekend::PI * area * sides)
     }
 }
 
@@ -180,8 +1506,17 @@ impl Material for DiffuseLight {
     ) -> bool {
         false // 不进行散射
     }
-    fn emitted(&self, u: f64, v: f64, p: &Point3) -> Color {
-        self.emit.value(u, v, p)
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        if !rec.front_face && !self.two_sided {
+            return Color::default();
+        }
+        let emit = self.power_scale() * self.emit.value(rec.u, rec.v, &rec.p);
+        if self.exponent == 0.0 {
+            return emit;
+        }
+        // rec.normal 总是朝向入射光线一侧
+        let cos_theta = -vec3::dot(vec3::unit_vector(r_in.direction()), rec.normal);
+        cos_theta.max(0.0).powf(self.exponent) * emit
     }
 }
 
@@ -212,4 +1547,220 @@ impl Material for Isotropic {
         *attenuation = self.tex.value(rec.u, rec.v, &rec.p);
         true
     }
+
+    fn eval(&self, _r_in: &Ray, rec: &HitRecord, _direction: Vec3) -> Option<Color> {
+        Some(self.tex.value(rec.u, rec.v, &rec.p) / (4.0 * rtweekend::PI))
+    }
+}
+
+// Anisotropic  按任意相函数散射的体散射材质，颜色为单次散射反照率
+pub struct Anisotropic {
+    tex: Arc<dyn Texture>,
+    phase: Arc<dyn PhaseFunction>,
+}
+
+impl Anisotropic {
+    pub fn new_from_color(albedo: Color, phase: Arc<dyn PhaseFunction>) -> Self {
+        Self::new_from_texture(Arc::new(SolidColor::new(albedo)), phase)
+    }
+    pub fn new_from_texture(texture: Arc<dyn Texture>, phase: Arc<dyn PhaseFunction>) -> Self {
+        Self {
+            tex: texture,
+            phase,
+        }
+    }
+}
+
+impl Material for Anisotropic {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+    ) -> bool {
+        *scattered = Ray::new(rec.p, self.phase.sample(r_in.direction()), r_in.time());
+        *attenuation = self.tex.value(rec.u, rec.v, &rec.p);
+        true
+    }
+
+    fn eval(&self, r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Option<Color> {
+        let phase = self.phase.eval(r_in.direction(), direction);
+        Some(phase * self.tex.value(rec.u, rec.v, &rec.p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: usize = 20_000;
+    // 每次散射的权重不超过 1 左右，2 万个样本的均值标准差在 0.004 以内
+    const TOLERANCE: f64 = 0.015;
+    const ROUGHNESS: [f64; 6] = [0.0, 0.1, 0.25, 0.5, 0.75, 1.0];
+    const COS_THETA: [f64; 4] = [1.0, 0.7, 0.3, 0.1];
+
+    // z 轴朝上的表面和从入射角 cos_theta 射向原点的光线
+    fn surface(cos_theta: f64) -> (HitRecord, Ray) {
+        let rec = HitRecord {
+            normal: Vec3::new(0.0, 0.0, 1.0),
+            geometric_normal: Vec3::new(0.0, 0.0, 1.0),
+            tangent: Vec3::new(1.0, 0.0, 0.0),
+            bitangent: Vec3::new(0.0, 1.0, 0.0),
+            front_face: true,
+            u: 0.5,
+            v: 0.5,
+            ..Default::default()
+        };
+
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let r_in = Ray::new(
+            Point3::new(sin_theta, 0.0, cos_theta),
+            Vec3::new(-sin_theta, 0.0, -cos_theta),
+            0.0,
+        );
+        (rec, r_in)
+    }
+
+    // 白炉：在均匀白色环境中估计材质对入射角 cos_theta 的方向反照率
+    fn directional_albedo(mat: &dyn Material, cos_theta: f64) -> Color {
+        let (rec, r_in) = surface(cos_theta);
+        let mut sum = Color::default();
+        for _ in 0..SAMPLES {
+            let mut attenuation = Color::default();
+            let mut scattered = Ray::default();
+            if mat.scatter(&r_in, &rec, &mut attenuation, &mut scattered) {
+                sum += attenuation;
+            }
+        }
+        sum / SAMPLES as f64
+    }
+
+    fn white() -> Color {
+        Color::new(1.0, 1.0, 1.0)
+    }
+
+    fn assert_conserves_energy(name: &str, mat: &dyn Material) {
+        for cos_theta in COS_THETA {
+            let albedo = directional_albedo(mat, cos_theta);
+            for c in [albedo.x, albedo.y, albedo.z] {
+                assert!(
+                    c <= 1.0 + TOLERANCE,
+                    "{name}: albedo {c} > 1 at cos_theta {cos_theta}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rough_materials_conserve_energy() {
+        for roughness in ROUGHNESS {
+            let white_diffuse = Arc::new(Lambertian::new(white()));
+            let materials: [(&str, Box<dyn Material>); 5] = [
+                ("metal", Box::new(Metal::new(white(), roughness))),
+                (
+                    "rough dielectric",
+                    Box::new(RoughDielectric::new(1.5, roughness)),
+                ),
+                (
+                    "coated diffuse",
+                    Box::new(Coated::new(white_diffuse, 1.5, roughness)),
+                ),
+                ("oren-nayar", Box::new(OrenNayar::new(white(), roughness))),
+                (
+                    "translucent",
+                    Box::new(Translucent::new(
+                        Color::new(0.5, 0.5, 0.5),
+                        Color::new(0.5, 0.5, 0.5),
+                    )),
+                ),
+            ];
+            for (name, mat) in &materials {
+                assert_conserves_energy(&format!("{name} (roughness {roughness})"), mat.as_ref());
+            }
+        }
+    }
+
+    #[test]
+    fn smooth_white_conductor_is_lossless() {
+        let metal = Metal::new(white(), 0.0);
+        for cos_theta in COS_THETA {
+            let albedo = directional_albedo(&metal, cos_theta);
+            assert!(
+                (albedo.x - 1.0).abs() < TOLERANCE,
+                "albedo {} at cos_theta {cos_theta}",
+                albedo.x
+            );
+        }
+    }
+
+    #[test]
+    fn smooth_dielectric_is_lossless() {
+        let materials: [(&str, Box<dyn Material>); 2] = [
+            ("dielectric", Box::new(Dielectric::new(1.5))),
+            ("rough dielectric", Box::new(RoughDielectric::new(1.5, 0.0))),
+        ];
+        for (name, mat) in &materials {
+            for cos_theta in COS_THETA {
+                let albedo = directional_albedo(mat.as_ref(), cos_theta);
+                assert!(
+                    (albedo.x - 1.0).abs() < TOLERANCE,
+                    "{name}: albedo {} at cos_theta {cos_theta}",
+                    albedo.x
+                );
+            }
+        }
+    }
+
+    // 同一反照率由 eval 在整个球面上均匀采样积分得到；均匀采样方差较大，多取一些样本
+    fn eval_albedo(mat: &dyn Material, cos_theta: f64) -> Color {
+        let (rec, r_in) = surface(cos_theta);
+        let mut sum = Color::default();
+        for _ in 0..SAMPLES * 5 {
+            let direction = vec3::random_unit_vector();
+            sum += mat.eval(&r_in, &rec, direction).expect("eval") * (4.0 * rtweekend::PI);
+        }
+        sum / (SAMPLES * 5) as f64
+    }
+
+    // 光源采样（eval）与 BSDF 采样（scatter）必须给出同一个 BSDF
+    #[test]
+    fn eval_matches_scatter() {
+        let tint = Color::new(0.9, 0.6, 0.3);
+        let roughness = 0.8;
+        let materials: [(&str, Box<dyn Material>); 6] = [
+            ("metal", Box::new(Metal::new(tint, roughness))),
+            (
+                "principled",
+                Box::new(Principled::new(tint, 0.0, roughness).with_sheen(white() * 0.5)),
+            ),
+            (
+                "principled metal",
+                Box::new(Principled::new(tint, 1.0, roughness)),
+            ),
+            (
+                "principled glass",
+                Box::new(Principled::new(white(), 0.0, roughness).with_transmission(1.0, 1.5)),
+            ),
+            (
+                "principled clearcoat",
+                Box::new(Principled::new(tint, 0.0, roughness).with_clearcoat(1.0, roughness)),
+            ),
+            (
+                "translucent",
+                Box::new(Translucent::new(tint * 0.5, tint * 0.4)),
+            ),
+        ];
+        for (name, mat) in &materials {
+            for cos_theta in COS_THETA {
+                let sampled = directional_albedo(mat.as_ref(), cos_theta);
+                let evaluated = eval_albedo(mat.as_ref(), cos_theta);
+                let diff = sampled - evaluated;
+                assert!(
+                    diff.x.abs().max(diff.y.abs()).max(diff.z.abs()) < 0.04,
+                    "{name} at cos_theta {cos_theta}: scatter {sampled:?}, eval {evaluated:?}"
+                );
+            }
+        }
+    }
 }
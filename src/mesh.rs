@@ -2,12 +2,13 @@
 use crate::aabb::Aabb;
 use crate::bvh::BvhNode;
 use crate::color::Color;
-use crate::hittable::{HitRecord, Hittable};
+use crate::hittable::{AlphaMask, AlphaMode, HitRecord, Hittable};
 use crate::interval::Interval;
-use crate::material::{DiffuseLight, Lambertian, Material, Metal};
+use crate::light::{DirectionalLight, Light, PointLight, SpotLight};
+use crate::material::{Lambertian, Material, NormalMapped, Principled};
 use crate::ray::Ray;
 use crate::rtw_image::RtwImage;
-use crate::texture::{ImageTexture, SolidColor, Texture};
+use crate::texture::{ChannelTexture, ImageTexture, ScaledTexture, SolidColor, Texture};
 use crate::triangle::Triangle;
 use crate::vec3::{cross, dot, Point3, Vec3};
 use std::collections::HashMap;
@@ -16,6 +17,7 @@ use std::sync::Arc;
 pub struct Mesh {
     bvh_root: Arc<dyn Hittable + Send + Sync>,
     bbox: Aabb,
+    lights: Vec<Arc<dyn Light>>,
 }
 impl Mesh {
     pub fn new(file_path: &str) -> Self {
@@ -42,7 +44,21 @@ impl Mesh {
 
         let mut triangle_objects: Vec<Arc<dyn Hittable + Send + Sync>> = Vec::new();
 
-        for mesh in doc.meshes() {
+        // 按场景的节点层级实例化网格并收集光源；没有场景时直接使用所有网格
+        let mut instances = Vec::new();
+        let mut lights = Vec::new();
+        match doc.default_scene().or_else(|| doc.scenes().next()) {
+            Some(scene) => {
+                for node in scene.nodes() {
+                    collect_nodes(&node, IDENTITY, &mut instances, &mut lights);
+                }
+            }
+            None => instances.extend(doc.meshes().map(|mesh| (mesh, IDENTITY))),
+        }
+
+        for (mesh, transform) in instances {
+            // 负行列式（镜像）会翻转三角形的绕序，交换两个顶点以保持法线朝外
+            let mirrored = determinant(&transform) < 0.0;
             for primitive in mesh.primitives() {
                 // --- 智能材质创建 ---
                 let material_index = primitive.material().index();
@@ -52,45 +68,31 @@ impl Mesh {
                         if let Some(cached_mat) = material_cache.get(&index) {
                             Arc::clone(cached_mat)
                         } else {
-                            //  如果无缓存，则创建新材质
+                            //  如果无缓存，则按 glTF 参数创建 Principled 材质
                             let gltf_material = primitive.material();
-                            let pbr = gltf_material.pbr_metallic_roughness();
-
-                            // 检查是否有自发光属性
-                            let is_emissive = gltf_material.emissive_factor()[0] > 0.0
-                                || gltf_material.emissive_factor()[1] > 0.0
-                                || gltf_material.emissive_factor()[2] > 0.0;
-
-                            let new_mat: Arc<dyn Material + Send + Sync> = if is_emissive {
-                                // 创建发光材质
-                                let emissive_strength = 5.0; // 放大亮度以获得明显效果
-                                let color = Color::from_slice(&gltf_material.emissive_factor())
-                                    * emissive_strength;
-                                Arc::new(DiffuseLight::new_from_color(color))
-                            } else {
-                                // 创建 PBR 材质 (Metal or Lambertian)
-                                // 首先确定纹理：是图片还是纯色？
-                                let texture = get_or_create_texture(
-                                    &pbr,
+                            let mut new_mat: Arc<dyn Material + Send + Sync> =
+                                Arc::new(principled_from_gltf(
+                                    &gltf_material,
                                     &mut texture_cache,
                                     &doc,
                                     &buffers,
                                     model_dir,
-                                );
-                                // texture_cache.insert(
-                                //     pbr.base_color_texture().unwrap().texture().index(),
-                                //     Arc::clone(&texture),
-                                // );
-
-                                // 根据金属性决定创建 Metal 还是 Lambertian
-                                let metallic = pbr.metallic_factor();
-                                let roughness = pbr.roughness_factor();
-                                if metallic > 0.5 {
-                                    Arc::new(Metal::new_from_texture(texture, roughness as f64))
-                                } else {
-                                    Arc::new(Lambertian::new_from_texture(texture))
-                                }
-                            };
+                                ));
+                            // 切线空间法线贴图
+                            if let Some(normal) = gltf_material.normal_texture()
+                                && let Some(normal_map) = get_or_create_texture(
+                                    &normal.texture(),
+                                    &mut texture_cache,
+                                    &buffers,
+                                    model_dir,
+                                )
+                            {
+                                new_mat = Arc::new(NormalMapped::new(
+                                    new_mat,
+                                    normal_map,
+                                    normal.scale() as f64,
+                                ));
+                            }
 
                             material_cache.insert(index, Arc::clone(&new_mat));
                             new_mat
@@ -99,21 +101,50 @@ impl Mesh {
                     None => Arc::clone(&default_material),
                 };
 
+                let alpha = alpha_mask_from_gltf(
+                    &primitive.material(),
+                    &mut texture_cache,
+                    &buffers,
+                    model_dir,
+                );
+
                 let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
                 if let Some(pos_iter) = reader.read_positions() {
                     let positions: Vec<Point3> = pos_iter
-                        .map(|p| Point3::new(p[0] as f64, p[1] as f64, p[2] as f64))
+                        .map(|p| {
+                            transform_point(
+                                &transform,
+                                Point3::new(p[0] as f64, p[1] as f64, p[2] as f64),
+                            )
+                        })
                         .collect();
 
+                    // glTF 纹理坐标原点在左上角，ImageTexture 的 v 向上，这里翻转 v
+                    let uvs: Option<Vec<(f64, f64)>> = reader.read_tex_coords(0).map(|tc| {
+                        tc.into_f32()
+                            .map(|uv| (uv[0] as f64, 1.0 - uv[1] as f64))
+                            .collect()
+                    });
+
                     if let Some(index_iter) = reader.read_indices() {
                         let indices: Vec<u32> = index_iter.into_u32().collect();
 
                         for face_indices in indices.chunks_exact(3) {
-                            let p0 = positions[face_indices[0] as usize];
-                            let p1 = positions[face_indices[1] as usize];
-                            let p2 = positions[face_indices[2] as usize];
-                            let tri = Arc::new(Triangle::new(p0, p1, p2, Arc::clone(&rust_mat)));
-                            triangle_objects.push(tri);
+                            let [i0, mut i1, mut i2] = [0, 1, 2].map(|k| face_indices[k] as usize);
+                            if mirrored {
+                                std::mem::swap(&mut i1, &mut i2);
+                            }
+                            let p0 = positions[i0];
+                            let p1 = positions[i1];
+                            let p2 = positions[i2];
+                            let mut tri = Triangle::new(p0, p1, p2, Arc::clone(&rust_mat));
+                            if let Some(uvs) = &uvs {
+                                tri = tri.with_uvs(uvs[i0], uvs[i1], uvs[i2]);
+                            }
+                            if let Some(alpha) = &alpha {
+                                tri = tri.with_alpha(alpha.clone());
+                            }
+                            triangle_objects.push(Arc::new(tri));
                         }
                     }
                 }
@@ -126,83 +157,284 @@ impl Mesh {
         let bvh_root = Arc::new(BvhNode::new(&mut triangle_objects));
         let bbox = bvh_root.bounding_box();
         eprintln!("[MESH] BVH built.");
-        Self { bvh_root, bbox }
+
+        if !lights.is_empty() {
+            eprintln!("[MESH] Imported {} punctual lights.", lights.len());
+        }
+        Self {
+            bvh_root,
+            bbox,
+            lights,
+        }
+    }
+
+    /// 模型中的 KHR_lights_punctual 光源，与三角形一样已按节点层级变换，加入 camera.lights 后生效；
+    /// 对模型套用 Translate、RotateY 等变换时光源不会随之移动
+    pub fn lights(&self) -> &[Arc<dyn Light>] {
+        &self.lights
     }
 }
 
-fn get_or_create_texture(
-    pbr: &gltf::material::PbrMetallicRoughness,
+type Matrix4 = [[f64; 4]; 4]; // 列主序，与 glTF 一致
+
+const IDENTITY: Matrix4 = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+fn mul(a: &Matrix4, b: &Matrix4) -> Matrix4 {
+    let mut m = [[0.0; 4]; 4];
+    for (col, b_col) in m.iter_mut().zip(b) {
+        for (row, value) in col.iter_mut().enumerate() {
+            *value = (0..4).map(|k| a[k][row] * b_col[k]).sum();
+        }
+    }
+    m
+}
+
+fn transform_point(m: &Matrix4, p: Point3) -> Point3 {
+    let row = |i: usize| m[0][i] * p.x + m[1][i] * p.y + m[2][i] * p.z + m[3][i];
+    Point3::new(row(0), row(1), row(2))
+}
+
+// 左上 3×3 线性部分的行列式
+fn determinant(m: &Matrix4) -> f64 {
+    let [a, b, c] = [m[0], m[1], m[2]];
+    a[0] * (b[1] * c[2] - b[2] * c[1]) - b[0] * (a[1] * c[2] - a[2] * c[1])
+        + c[0] * (a[1] * b[2] - a[2] * b[1])
+}
+
+// 按节点层级累积变换，收集带网格的节点及其变换，以及光源。光源位于节点原点、沿局部 -z 照射。
+// intensity 的单位按 KHR_lights_punctual：点光源和聚光灯为 cd，平行光为 lux；range 忽略，始终按平方反比衰减
+fn collect_nodes<'a>(
+    node: &gltf::Node<'a>,
+    parent: Matrix4,
+    instances: &mut Vec<(gltf::Mesh<'a>, Matrix4)>,
+    lights: &mut Vec<Arc<dyn Light>>,
+) {
+    let local = node.transform().matrix().map(|col| col.map(|v| v as f64));
+    let world = mul(&parent, &local);
+    if let Some(mesh) = node.mesh() {
+        instances.push((mesh, world));
+    }
+    if let Some(light) = node.light() {
+        let position = Point3::new(world[3][0], world[3][1], world[3][2]);
+        let forward = -Vec3::new(world[2][0], world[2][1], world[2][2]).unit_vector();
+        let [r, g, b] = light.color();
+        let color = Color::new(r as f64, g as f64, b as f64);
+        let intensity = light.intensity() as f64;
+        let imported: Arc<dyn Light> = match light.kind() {
+            gltf::khr_lights_punctual::Kind::Directional => {
+                Arc::new(DirectionalLight::new(-forward, color, intensity))
+            }
+            gltf::khr_lights_punctual::Kind::Point => {
+                Arc::new(PointLight::new(position, color, intensity))
+            }
+            gltf::khr_lights_punctual::Kind::Spot {
+                inner_cone_angle,
+                outer_cone_angle,
+            } => Arc::new(SpotLight::new(
+                position,
+                forward,
+                color,
+                intensity,
+                (inner_cone_angle as f64).to_degrees(),
+                (outer_cone_angle as f64).to_degrees(),
+            )),
+        };
+        lights.push(imported);
+    }
+    for child in node.children() {
+        collect_nodes(&child, world, instances, lights);
+    }
+}
+
+// glTF metallic-roughness 材质及其扩展一一映射到 Principled：
+// 每个参数都是 factor × texture（缺少贴图时为常量）
+fn principled_from_gltf(
+    material: &gltf::Material,
     cache: &mut HashMap<usize, Arc<dyn Texture + Send + Sync>>,
     doc: &gltf::Document,
     buffers: &[gltf::buffer::Data],
     model_dir: &Path,
+) -> Principled {
+    let pbr = material.pbr_metallic_roughness();
+    let mut load = |texture: Option<gltf::Texture>| {
+        texture.and_then(|t| get_or_create_texture(&t, cache, buffers, model_dir))
+    };
+
+    let base = pbr.base_color_factor();
+    let base_color = scaled(
+        load(pbr.base_color_texture().map(|info| info.texture())),
+        Color::new(base[0] as f64, base[1] as f64, base[2] as f64),
+    );
+
+    // metallicRoughness 贴图：G 通道为粗糙度，B 通道为金属度
+    let metallic_roughness = load(pbr.metallic_roughness_texture().map(|info| info.texture()));
+    let metallic = channel(metallic_roughness.clone(), 2, pbr.metallic_factor() as f64);
+    let roughness = channel(metallic_roughness, 1, pbr.roughness_factor() as f64);
+
+    // emissiveFactor 限制在 [0, 1]，更亮的自发光由 KHR_materials_emissive_strength 给出倍数
+    let emissive_strength = material.emissive_strength().unwrap_or(1.0) as f64;
+    let emission = scaled(
+        load(material.emissive_texture().map(|info| info.texture())),
+        Color::from_slice(&material.emissive_factor()) * emissive_strength,
+    );
+
+    let mut principled = Principled {
+        metallic,
+        roughness,
+        emission,
+        two_sided_emission: material.double_sided(),
+        ior: material.ior().unwrap_or(1.5) as f64,
+        ..Principled::new_from_texture(base_color)
+    };
+
+    if let Some(transmission) = material.transmission() {
+        principled.transmission = channel(
+            load(
+                transmission
+                    .transmission_texture()
+                    .map(|info| info.texture()),
+            ),
+            0,
+            transmission.transmission_factor() as f64,
+        );
+    }
+    if let Some(specular) = material.specular() {
+        // specularTexture 存放在 alpha 通道，这里只使用强度因子和颜色贴图
+        let color = specular.specular_color_factor();
+        principled.specular = scaled(
+            load(specular.specular_color_texture().map(|info| info.texture())),
+            Color::new(color[0] as f64, color[1] as f64, color[2] as f64)
+                * specular.specular_factor() as f64,
+        );
+    }
+
+    // gltf crate 没有封装清漆与光泽扩展，直接读取原始 JSON
+    let ext_factor = |ext: &str, key: &str| {
+        material
+            .extension_value(ext)
+            .and_then(|v| v.get(key))
+            .and_then(|v| v.as_f64())
+    };
+    let ext_texture = |ext: &str, key: &str| {
+        material
+            .extension_value(ext)
+            .and_then(|v| v.get(key))
+            .and_then(|v| v.get("index"))
+            .and_then(|v| v.as_u64())
+            .and_then(|index| doc.textures().nth(index as usize))
+    };
+    if let Some(clearcoat) = ext_factor("KHR_materials_clearcoat", "clearcoatFactor") {
+        principled.clearcoat = channel(
+            load(ext_texture("KHR_materials_clearcoat", "clearcoatTexture")),
+            0,
+            clearcoat,
+        );
+        principled.clearcoat_roughness = channel(
+            load(ext_texture(
+                "KHR_materials_clearcoat",
+                "clearcoatRoughnessTexture",
+            )),
+            1,
+            ext_factor("KHR_materials_clearcoat", "clearcoatRoughnessFactor").unwrap_or(0.0),
+        );
+    }
+    if let Some(sheen) = material
+        .extension_value("KHR_materials_sheen")
+        .and_then(|v| v.get("sheenColorFactor"))
+        .and_then(|v| v.as_array())
+    {
+        let factor = |i: usize| sheen.get(i).and_then(|c| c.as_f64()).unwrap_or(0.0);
+        principled.sheen = scaled(
+            load(ext_texture("KHR_materials_sheen", "sheenColorTexture")),
+            Color::new(factor(0), factor(1), factor(2)),
+        );
+    }
+
+    principled
+}
+
+// alphaMode 为 MASK / BLEND 时，由 baseColor 的 alpha（factor × texture）构造透明度遮罩
+fn alpha_mask_from_gltf(
+    material: &gltf::Material,
+    cache: &mut HashMap<usize, Arc<dyn Texture + Send + Sync>>,
+    buffers: &[gltf::buffer::Data],
+    model_dir: &Path,
+) -> Option<AlphaMask> {
+    let mode = match material.alpha_mode() {
+        gltf::material::AlphaMode::Opaque => return None,
+        gltf::material::AlphaMode::Mask => AlphaMode::Mask {
+            cutoff: material.alpha_cutoff().unwrap_or(0.5) as f64,
+        },
+        gltf::material::AlphaMode::Blend => AlphaMode::Blend,
+    };
+
+    let pbr = material.pbr_metallic_roughness();
+    let texture = pbr
+        .base_color_texture()
+        .and_then(|info| get_or_create_texture(&info.texture(), cache, buffers, model_dir))
+        .unwrap_or_else(|| Arc::new(SolidColor::new(Color::new(1.0, 1.0, 1.0))));
+    let alpha = ScaledTexture::new(texture, Color::new(1.0, 1.0, 1.0))
+        .with_alpha(pbr.base_color_factor()[3] as f64);
+    Some(AlphaMask::new(Arc::new(alpha), mode))
+}
+
+// factor × texture，没有贴图时退化为纯色
+fn scaled(
+    texture: Option<Arc<dyn Texture + Send + Sync>>,
+    factor: Color,
 ) -> Arc<dyn Texture + Send + Sync> {
-    if let Some(texture_info) = pbr.base_color_texture() {
-        let texture_index = texture_info.texture().index();
-        if let Some(cached_tex) = cache.get(&texture_index) {
-            return Arc::clone(cached_tex);
-        }
+    match texture {
+        Some(texture) => Arc::new(ScaledTexture::new(texture, factor)),
+        None => Arc::new(SolidColor::new(factor)),
+    }
+}
 
-        let gltf_texture = doc.textures().nth(texture_index).unwrap();
-
-        match gltf_texture.source().source() {
-            // 处理外部文件 (URI)
-            gltf::image::Source::Uri { uri, .. } => {
-                let image_path = model_dir.join(uri);
-                // eprintln!(
-                //     // "[TEXTURE] Attempting to load texture from URI: {:?}",
-                //     image_path
-                // );
-
-                // 直接使用 image::open，绕过 RtwImage 的 search_paths
-                match image::open(&image_path) {
-                    Ok(loaded_image) => {
-                        // 使用新加的构造函数
-                        let rtw_image = RtwImage::from_image(loaded_image);
-                        let image_tex = ImageTexture::from_rtw_image(rtw_image);
-
-                        // eprintln!("[TEXTURE] SUCCESS: Loaded from URI.");
-                        let new_tex: Arc<dyn Texture + Send + Sync> = Arc::new(image_tex);
-                        cache.insert(texture_index, Arc::clone(&new_tex));
-                        return new_tex;
-                    }
-                    Err(e) => {}
-                }
-            }
-            // 处理嵌入式纹理
-            gltf::image::Source::View { view, mime_type } => {
-                // eprintln!(
-                //     // "[TEXTURE] Loading embedded texture (mime type: {})...",
-                //     mime_type
-                // );
-                let buffer_data = &buffers[view.buffer().index()];
-                let image_data_slice = &buffer_data[view.offset()..view.offset() + view.length()];
-
-                match image::load_from_memory(image_data_slice) {
-                    Ok(loaded_image) => {
-                        // 使用新加的构造函数
-                        let rtw_image = RtwImage::from_image(loaded_image);
-                        let image_tex = ImageTexture::from_rtw_image(rtw_image);
-
-                        // eprintln!("[TEXTURE] SUCCESS: Loaded embedded image.");
-                        let new_tex: Arc<dyn Texture + Send + Sync> = Arc::new(image_tex);
-                        cache.insert(texture_index, Arc::clone(&new_tex));
-                        return new_tex;
-                    }
-                    Err(e) => {}
-                }
-            }
-        }
+// factor × texture 的单个通道，没有贴图时退化为常量
+fn channel(
+    texture: Option<Arc<dyn Texture + Send + Sync>>,
+    channel: usize,
+    factor: f64,
+) -> Arc<dyn Texture + Send + Sync> {
+    match texture {
+        Some(texture) => Arc::new(ChannelTexture::new(texture, channel, factor)),
+        None => Arc::new(SolidColor::new(Color::new(factor, factor, factor))),
     }
+}
+
+fn get_or_create_texture(
+    gltf_texture: &gltf::Texture,
+    cache: &mut HashMap<usize, Arc<dyn Texture + Send + Sync>>,
+    buffers: &[gltf::buffer::Data],
+    model_dir: &Path,
+) -> Option<Arc<dyn Texture + Send + Sync>> {
+    let texture_index = gltf_texture.index();
+    if let Some(cached_tex) = cache.get(&texture_index) {
+        return Some(Arc::clone(cached_tex));
+    }
+
+    let loaded_image = match gltf_texture.source().source() {
+        // 处理外部文件 (URI)
+        gltf::image::Source::Uri { uri, .. } => {
+            // 直接使用 image::open，绕过 RtwImage 的 search_paths
+            image::open(model_dir.join(uri)).ok()?
+        }
+        // 处理嵌入式纹理
+        gltf::image::Source::View { view, .. } => {
+            let buffer_data = &buffers[view.buffer().index()];
+            let image_data_slice = &buffer_data[view.offset()..view.offset() + view.length()];
+            image::load_from_memory(image_data_slice).ok()?
+        }
+    };
 
-    // 以上都失败，则使用纯色作为后备
-    // eprintln!("[TEXTURE] FALLBACK: Using solid color for this material.");
-    let color_factor = pbr.base_color_factor();
-    Arc::new(SolidColor::new(Color::new(
-        color_factor[0] as f64,
-        color_factor[1] as f64,
-        color_factor[2] as f64,
-    )))
+    let rtw_image = RtwImage::from_image(loaded_image);
+    let new_tex: Arc<dyn Texture + Send + Sync> = Arc::new(ImageTexture::from_rtw_image(rtw_image));
+    cache.insert(texture_index, Arc::clone(&new_tex));
+    Some(new_tex)
 }
 
 impl Hittable for Mesh {
@@ -0,0 +1,180 @@
+//microfacet.rs
+// 所有方向均在局部着色坐标系中，z 轴为宏观法线
+use crate::color::Color;
+use crate::conductor::ConductorSpectrum;
+use crate::rtweekend;
+use crate::vec3;
+use crate::vec3::Vec3;
+
+/// GGX / Trowbridge-Reitz 法线分布
+#[derive(Debug, Clone, Copy)]
+pub struct TrowbridgeReitz {
+    alpha: f64,
+}
+
+impl TrowbridgeReitz {
+    // 小于该值时视为理想镜面，避免数值问题
+    const SMOOTH_ALPHA: f64 = 1e-3;
+
+    pub fn new(alpha: f64) -> Self {
+        Self { alpha }
+    }
+
+    /// 由感知粗糙度（glTF roughness）构造，alpha = roughness²
+    pub fn from_roughness(roughness: f64) -> Self {
+        let r = roughness.clamp(0.0, 1.0);
+        Self { alpha: r * r }
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    pub fn effectively_smooth(&self) -> bool {
+        self.alpha < Self::SMOOTH_ALPHA
+    }
+
+    /// 法线分布函数 D(wm)
+    pub fn d(&self, wm: Vec3) -> f64 {
+        let cos2 = wm.z * wm.z;
+        if cos2 <= 0.0 {
+            return 0.0;
+        }
+        let a2 = self.alpha * self.alpha;
+        let denom = cos2 * (a2 - 1.0) + 1.0;
+        a2 / (rtweekend::PI * denom * denom)
+    }
+
+    /// Smith Λ(w)
+    pub fn lambda(&self, w: Vec3) -> f64 {
+        let cos2 = w.z * w.z;
+        if cos2 <= 0.0 {
+            return 0.0;
+        }
+        let tan2 = (1.0 - cos2).max(0.0) / cos2;
+        0.5 * ((1.0 + self.alpha * self.alpha * tan2).sqrt() - 1.0)
+    }
+
+    /// 单方向遮蔽函数 G1(w)
+    pub fn g1(&self, w: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    /// 高度相关的 Smith 遮蔽-阴影函数 G2(wo, wi)
+    pub fn g(&self, wo: Vec3, wi: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// 从 wo 可见的法线分布 D_wo(wm) = G1(wo) max(0, wo·wm) D(wm) / cosθo
+    pub fn visible_d(&self, wo: Vec3, wm: Vec3) -> f64 {
+        if wo.z.abs() <= 0.0 {
+            return 0.0;
+        }
+        self.g1(wo) * vec3::dot(wo, wm).max(0.0) * self.d(wm) / wo.z.abs()
+    }
+
+    /// 按可见法线分布采样微表面法线（Heitz 2018）
+    pub fn sample_wm(&self, wo: Vec3) -> Vec3 {
+        let wo = if wo.z < 0.0 { -wo } else { wo };
+        // 拉伸到 alpha = 1 的半球
+        let wh = vec3::unit_vector(Vec3::new(self.alpha * wo.x, self.alpha * wo.y, wo.z));
+        let t1 = if wh.z < 0.99999 {
+            vec3::unit_vector(vec3::cross(Vec3::new(0.0, 0.0, 1.0), wh))
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let t2 = vec3::cross(wh, t1);
+
+        // 在单位圆盘上均匀采样，再按可见半球扭曲
+        let r = rtweekend::random_double().sqrt();
+        let phi = 2.0 * rtweekend::PI * rtweekend::random_double();
+        let px = r * phi.cos();
+        let py = r * phi.sin();
+        let s = 0.5 * (1.0 + wh.z);
+        let h = (1.0 - px * px).max(0.0).sqrt();
+        let py = (1.0 - s) * h + s * py;
+        let pz = (1.0 - px * px - py * py).max(0.0).sqrt();
+        let nh = px * t1 + py * t2 + pz * wh;
+
+        // 反拉伸
+        vec3::unit_vector(Vec3::new(
+            self.alpha * nh.x,
+            self.alpha * nh.y,
+            nh.z.max(1e-6),
+        ))
+    }
+}
+
+/// 菲涅尔模型
+#[derive(Debug, Clone, Copy)]
+pub enum Fresnel {
+    /// Schlick 近似，F0 由材质的 albedo 给出
+    Schlick,
+    /// 导体的复折射率 eta + i·k（按 RGB 通道）
+    Conductor { eta: Color, k: Color },
+    /// 实测导体光谱：光谱模式按波长查表，RGB 模式使用预先投影的各通道 eta、k
+    Measured {
+        spectrum: &'static ConductorSpectrum,
+        eta: Color,
+        k: Color,
+    },
+}
+
+impl Fresnel {
+    pub fn measured(spectrum: &'static ConductorSpectrum) -> Self {
+        let (eta, k) = spectrum.eta_k_rgb();
+        Fresnel::Measured { spectrum, eta, k }
+    }
+}
+
+pub fn schlick(f0: Color, cos_theta: f64) -> Color {
+    let m = (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5);
+    f0 + (Color::new(1.0, 1.0, 1.0) - f0) * m
+}
+
+/// 电介质菲涅尔反射率，eta 为透射侧与入射侧折射率之比
+pub fn fresnel_dielectric(cos_theta_i: f64, eta: f64) -> f64 {
+    let (cos_i, eta) = if cos_theta_i < 0.0 {
+        (-cos_theta_i, 1.0 / eta)
+    } else {
+        (cos_theta_i, eta)
+    };
+    let sin2_t = (1.0 - cos_i * cos_i).max(0.0) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return 1.0; // 全反射
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+    let r_parl = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let r_perp = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    0.5 * (r_parl * r_parl + r_perp * r_perp)
+}
+
+/// 单通道导体菲涅尔反射率（复折射率 eta + i·k）
+pub fn fresnel_conductor_scalar(cos_theta_i: f64, eta: f64, k: f64) -> f64 {
+    let cos2 = cos_theta_i.clamp(0.0, 1.0).powi(2);
+    let sin2 = 1.0 - cos2;
+    let eta2 = eta * eta;
+    let k2 = k * k;
+
+    let t0 = eta2 - k2 - sin2;
+    let a2_plus_b2 = (t0 * t0 + 4.0 * eta2 * k2).sqrt();
+    let t1 = a2_plus_b2 + cos2;
+    let a = (0.5 * (a2_plus_b2 + t0)).max(0.0).sqrt();
+    let t2 = 2.0 * cos_theta_i.clamp(0.0, 1.0) * a;
+    let rs = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2_plus_b2 + sin2 * sin2;
+    let t4 = t2 * sin2;
+    let rp = rs * (t3 - t4) / (t3 + t4);
+
+    0.5 * (rp + rs)
+}
+
+/// 按 RGB 通道计算导体菲涅尔反射率
+pub fn fresnel_conductor(cos_theta_i: f64, eta: Color, k: Color) -> Color {
+    Color::new(
+        fresnel_conductor_scalar(cos_theta_i, eta.x, k.x),
+        fresnel_conductor_scalar(cos_theta_i, eta.y, k.y),
+        fresnel_conductor_scalar(cos_theta_i, eta.z, k.z),
+    )
+}
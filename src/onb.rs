@@ -0,0 +1,49 @@
+//onb.rs
+use crate::vec3;
+use crate::vec3::Vec3;
+
+/// 以法线为 w 轴的正交基，用于在局部着色坐标系和世界坐标系之间转换
+#[derive(Debug, Clone, Copy)]
+pub struct Onb {
+    axis: [Vec3; 3],
+}
+
+impl Onb {
+    pub fn new(n: Vec3) -> Self {
+        let w = vec3::unit_vector(n);
+        let a = if w.x.abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let v = vec3::unit_vector(vec3::cross(w, a));
+        let u = vec3::cross(w, v);
+        Self { axis: [u, v, w] }
+    }
+
+    pub fn u(&self) -> Vec3 {
+        self.axis[0]
+    }
+
+    pub fn v(&self) -> Vec3 {
+        self.axis[1]
+    }
+
+    pub fn w(&self) -> Vec3 {
+        self.axis[2]
+    }
+
+    /// 局部坐标 -> 世界坐标
+    pub fn transform(&self, v: Vec3) -> Vec3 {
+        v.x * self.axis[0] + v.y * self.axis[1] + v.z * self.axis[2]
+    }
+
+    /// 世界坐标 -> 局部坐标
+    pub fn to_local(&self, v: Vec3) -> Vec3 {
+        Vec3::new(
+            vec3::dot(v, self.axis[0]),
+            vec3::dot(v, self.axis[1]),
+            vec3::dot(v, self.axis[2]),
+        )
+    }
+}
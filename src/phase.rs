@@ -0,0 +1,85 @@
+//phase.rs
+// 参与介质的相函数
+use crate::onb::Onb;
+use crate::rtweekend;
+use crate::vec3::{self, Vec3};
+
+/// 相函数：在散射点按角分布采样新的传播方向
+pub trait PhaseFunction: Send + Sync {
+    /// dir 为入射光线的传播方向；采样与相函数成正比，权重恒为 1
+    fn sample(&self, dir: Vec3) -> Vec3;
+    /// 从 dir 散射到 scattered 的相函数值（在整个球面上积分为 1），用于光源采样
+    fn eval(&self, dir: Vec3, scattered: Vec3) -> f64;
+}
+
+/// Henyey–Greenstein 相函数，g ∈ (-1, 1)：正值前向散射，负值后向散射，0 为各向同性
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HenyeyGreenstein {
+    pub g: f64,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(g: f64) -> Self {
+        Self {
+            g: g.clamp(-0.99, 0.99),
+        }
+    }
+}
+
+impl PhaseFunction for HenyeyGreenstein {
+    fn sample(&self, dir: Vec3) -> Vec3 {
+        let g = self.g;
+        let xi = rtweekend::random_double();
+        let cos_theta = if g.abs() < 1e-3 {
+            1.0 - 2.0 * xi
+        } else {
+            let sqr = (1.0 - g * g) / (1.0 - g + 2.0 * g * xi);
+            ((1.0 + g * g - sqr * sqr) / (2.0 * g)).clamp(-1.0, 1.0)
+        };
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * rtweekend::PI * rtweekend::random_double();
+        let local = Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+        Onb::new(vec3::unit_vector(dir)).transform(local)
+    }
+
+    fn eval(&self, dir: Vec3, scattered: Vec3) -> f64 {
+        let g = self.g;
+        let cos_theta = vec3::dot(vec3::unit_vector(dir), vec3::unit_vector(scattered));
+        let denom = 1.0 + g * g - 2.0 * g * cos_theta;
+        (1.0 - g * g) / (4.0 * rtweekend::PI * denom * denom.sqrt())
+    }
+}
+
+/// 双瓣 Henyey–Greenstein：前向瓣与后向瓣按 weight 混合（weight 为前向瓣的比例），
+/// 用于云层这类既有强前向散射又有明显后向光晕的介质
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DoubleHenyeyGreenstein {
+    pub forward: HenyeyGreenstein,
+    pub backward: HenyeyGreenstein,
+    pub weight: f64,
+}
+
+impl DoubleHenyeyGreenstein {
+    pub fn new(g_forward: f64, g_backward: f64, weight: f64) -> Self {
+        Self {
+            forward: HenyeyGreenstein::new(g_forward),
+            backward: HenyeyGreenstein::new(g_backward),
+            weight: weight.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl PhaseFunction for DoubleHenyeyGreenstein {
+    fn sample(&self, dir: Vec3) -> Vec3 {
+        if rtweekend::random_double() < self.weight {
+            self.forward.sample(dir)
+        } else {
+            self.backward.sample(dir)
+        }
+    }
+
+    fn eval(&self, dir: Vec3, scattered: Vec3) -> f64 {
+        self.weight * self.forward.eval(dir, scattered)
+            + (1.0 - self.weight) * self.backward.eval(dir, scattered)
+    }
+}
@@ -1,6 +1,6 @@
 //quad.rs
 use crate::aabb::Aabb;
-use crate::hittable::{HitRecord, Hittable};
+use crate::hittable::{AlphaMask, HitRecord, Hittable};
 use crate::hittable_list::HittableList;
 use crate::interval::Interval;
 use crate::material::Material;
@@ -17,6 +17,7 @@ pub struct Quad {
     normal: Vec3,
     D: f64,
     w: Vec3,
+    alpha: Option<AlphaMask>,
 }
 
 impl Quad {
@@ -34,11 +35,18 @@ impl Quad {
             normal,
             D,
             w,
+            alpha: None,
         };
         quad.set_bounding_box(); // 初始化时设置包围盒
         quad
     }
 
+    /// 设置透明度遮罩，被遮罩的位置光线直接穿过
+    pub fn with_alpha(mut self, alpha: AlphaMask) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
     fn set_bounding_box(&mut self) {
         // 构造两个对角线包围盒，再取并集
         let bbox1 = Aabb::new_points(self.q, self.q + self.u + self.v);
@@ -46,13 +54,19 @@ impl Quad {
         self.bbox = Aabb::new_boxes(&bbox1, &bbox2);
     }
 
-    fn is_interior(&self, a: f64, b: f64, rec: &mut HitRecord) -> bool {
+    fn is_interior(&self, a: f64, b: f64, p: &Point3, rec: &mut HitRecord) -> bool {
         let unit_interval = Interval::new(0.0, 1.0);
 
         // 若 a 或 b 超出 [0,1] 范围，说明不在矩形内部
         if !unit_interval.contains(a) || !unit_interval.contains(b) {
             return false;
         }
+        // 被透明度遮罩镂空的位置
+        if let Some(alpha) = &self.alpha
+            && !alpha.is_opaque(a, b, p)
+        {
+            return false;
+        }
 
         rec.u = a;
         rec.v = b;
@@ -80,7 +94,7 @@ impl Hittable for Quad {
         let alpha = vec3::dot(self.w, vec3::cross(planar_hitpt_vector, self.v));
         let beta = vec3::dot(self.w, vec3::cross(self.u, planar_hitpt_vector));
 
-        if !self.is_interior(alpha, beta, rec) {
+        if !self.is_interior(alpha, beta, &intersection, rec) {
             return false;
         }
 
@@ -88,6 +102,7 @@ impl Hittable for Quad {
         rec.p = intersection;
         rec.mat = Arc::clone(&self.mat);
         rec.set_face_normal(r, self.normal);
+        rec.set_tangents(self.u, self.v);
 
         true
     }
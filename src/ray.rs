@@ -6,12 +6,18 @@ pub struct Ray {
     orig: Point3,
     dir: Vec3,
     tm: f64,
+    wavelength: Option<f64>, // 光谱模式下的 hero 波长（nm），RGB 模式为 None
 }
 
 impl Ray {
     //  origin: 光线起点  direction: 光线方向
     pub fn new(orig: Point3, dir: Vec3, tm: f64) -> Self {
-        Self { orig, dir, tm }
+        Self {
+            orig,
+            dir,
+            tm,
+            wavelength: None,
+        }
     }
 
     pub fn origin(&self) -> Point3 {
@@ -25,6 +31,15 @@ impl Ray {
     pub fn time(&self) -> f64 {
         self.tm
     }
+
+    pub fn wavelength(&self) -> Option<f64> {
+        self.wavelength
+    }
+
+    pub fn with_wavelength(mut self, wavelength: Option<f64>) -> Self {
+        self.wavelength = wavelength;
+        self
+    }
     // 计算光线在参数 t 处的位置
     pub fn at(&self, t: f64) -> Point3 {
         self.orig + self.dir * t
@@ -38,6 +53,7 @@ impl Default for Ray {
             tm: 0.0,
             orig: Vec3::zero(),
             dir: Vec3::zero(),
+            wavelength: None,
         }
     }
 }
@@ -6,8 +6,8 @@ use std::{env, path::PathBuf};
 pub struct RtwImage {
     pub width: usize,
     pub height: usize,
-    pub fdata: Vec<f32>, // 每像素3个 float32，线性空间 [0.0, 1.0]
-    pub bdata: Vec<u8>,  // 同样是 RGB 排列，8-bit 色彩分量 [0,255]
+    pub fdata: Vec<f32>, // 每像素4个 float32（RGBA），线性空间 [0.0, 1.0]
+    pub bdata: Vec<u8>,  // 同样是 RGBA 排列，8-bit 色彩分量 [0,255]
 }
 
 impl RtwImage {
@@ -27,20 +27,17 @@ impl RtwImage {
     }
 
     pub fn from_image(img: DynamicImage) -> Self {
-        let img_rgb = img.to_rgb32f();
-        let (width, height) = img_rgb.dimensions();
-        let mut fdata = Vec::with_capacity((width * height * 3) as usize);
-        let mut bdata = Vec::with_capacity((width * height * 3) as usize);
-
-        for pixel in img_rgb.pixels() {
-            let [r, g, b] = pixel.0;
-            fdata.push(r);
-            fdata.push(g);
-            fdata.push(b);
-
-            bdata.push(Self::float_to_byte(r));
-            bdata.push(Self::float_to_byte(g));
-            bdata.push(Self::float_to_byte(b));
+        // 没有 alpha 通道的图片在转换时 alpha 为 1
+        let img_rgba = img.to_rgba32f();
+        let (width, height) = img_rgba.dimensions();
+        let mut fdata = Vec::with_capacity((width * height * 4) as usize);
+        let mut bdata = Vec::with_capacity((width * height * 4) as usize);
+
+        for pixel in img_rgba.pixels() {
+            for c in pixel.0 {
+                fdata.push(c);
+                bdata.push(Self::float_to_byte(c));
+            }
         }
 
         Self {
@@ -84,10 +81,21 @@ impl RtwImage {
         let x = x.clamp(0, (self.width - 1) as i32);
         let y = y.clamp(0, (self.height - 1) as i32);
 
-        let idx = (y as usize * self.width + x as usize) * 3;
+        let idx = (y as usize * self.width + x as usize) * 4;
         [self.bdata[idx], self.bdata[idx + 1], self.bdata[idx + 2]]
     }
 
+    pub fn alpha_data(&self, x: i32, y: i32) -> u8 {
+        if self.bdata.is_empty() {
+            return 255;
+        }
+
+        let x = x.clamp(0, (self.width - 1) as i32);
+        let y = y.clamp(0, (self.height - 1) as i32);
+
+        self.bdata[(y as usize * self.width + x as usize) * 4 + 3]
+    }
+
     fn float_to_byte(value: f32) -> u8 {
         if value <= 0.0 {
             0
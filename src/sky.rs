@@ -0,0 +1,256 @@
+//sky.rs
+// Preetham 解析日光模型（Preetham, Shirley, Smits 1999）：由太阳位置和浑浊度给出天空各方向的辐亮度，
+// 以及经过大气衰减后的太阳圆盘。输出为线性 RGB，单位 cd/m²，配合 PhysicalExposure 的曝光使用；
+// 不用物理曝光时可以用 with_scale 缩放到 [0,1] 附近。
+// 坐标约定：y 朝上，方位角从北（默认 -z）起向东（+x）顺时针计
+use crate::color::Color;
+use crate::light::{Light, LightSample};
+use crate::onb::Onb;
+use crate::rtweekend::{self, PI};
+use crate::spectrum::{self, LAMBDA_MAX, LAMBDA_MIN};
+use crate::vec3::{self, Point3, Vec3};
+
+/// 太阳的角半径（弧度）
+pub const SUN_ANGULAR_RADIUS: f64 = 0.004_65;
+// 大气层外太阳圆盘的亮度（cd/m²），约 1.3×10⁵ lx 的照度除以太阳的立体角
+const SUN_LUMINANCE: f64 = 1.9e9;
+// 太阳光谱近似为 5778K 黑体
+const SUN_TEMPERATURE: f64 = 5778.0;
+
+#[derive(Debug, Clone)]
+pub struct PreethamSky {
+    elevation: f64, // 太阳高度角（弧度）
+    azimuth: f64,   // 太阳方位角（弧度）
+    turbidity: f64,
+    north: Vec3,
+    sun_dir: Vec3,
+    perez: [[f64; 5]; 3], // Y, x, y 三组 Perez 系数 A..E
+    zenith: [f64; 3],     // 天顶的亮度（cd/m²）与色度 x, y
+    sun_radiance: Color,
+    scale: f64,
+}
+
+impl PreethamSky {
+    /// 太阳高度角、方位角（degrees）与浑浊度（2 为晴朗，10 为薄雾）
+    pub fn new(elevation: f64, azimuth: f64, turbidity: f64) -> Self {
+        let mut sky = Self {
+            elevation: rtweekend::degrees_to_radians(elevation),
+            azimuth: rtweekend::degrees_to_radians(azimuth),
+            turbidity: turbidity.clamp(1.7, 10.0),
+            north: Vec3::new(0.0, 0.0, -1.0),
+            sun_dir: Vec3::default(),
+            perez: [[0.0; 5]; 3],
+            zenith: [0.0; 3],
+            sun_radiance: Color::default(),
+            scale: 1.0,
+        };
+        sky.update();
+        sky
+    }
+
+    /// 由地点与时间确定太阳位置：纬度、经度（degrees，东经为正），
+    /// 一年中的第几天（1 月 1 日为 1），UTC 时间（小时）
+    pub fn from_location(
+        latitude: f64,
+        longitude: f64,
+        day_of_year: u32,
+        utc_hours: f64,
+        turbidity: f64,
+    ) -> Self {
+        let (elevation, azimuth) = solar_position(latitude, longitude, day_of_year, utc_hours);
+        Self::new(elevation, azimuth, turbidity)
+    }
+
+    /// 场景中北方对应的水平方向，用于让太阳方位与街道走向对齐
+    pub fn with_north(mut self, north: Vec3) -> Self {
+        self.north = vec3::unit_vector(Vec3::new(north.x, 0.0, north.z));
+        self.update();
+        self
+    }
+
+    /// 输出辐亮度的整体缩放
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// 指向太阳的单位向量
+    pub fn sun_direction(&self) -> Vec3 {
+        self.sun_dir
+    }
+
+    /// 经过大气衰减后太阳圆盘的辐亮度，地平线以下为 0
+    pub fn sun_radiance(&self) -> Color {
+        self.scale * self.sun_radiance
+    }
+
+    /// 沿 dir 方向看到的天空辐亮度；include_sun 为 false 时不含太阳圆盘（已通过光源采样计入）
+    pub fn radiance(&self, dir: Vec3, include_sun: bool) -> Color {
+        let dir = vec3::unit_vector(dir);
+        let cos_gamma = vec3::dot(dir, self.sun_dir).clamp(-1.0, 1.0);
+        let sun = if include_sun && cos_gamma >= SUN_ANGULAR_RADIUS.cos() {
+            self.sun_radiance
+        } else {
+            Color::default()
+        };
+
+        // 地平线以下沿用地平线的颜色
+        let cos_theta = dir.y.max(1e-3);
+        let theta_s = PI / 2.0 - self.elevation.max(0.0);
+        let gamma = cos_gamma.acos();
+        let mut yxy = [0.0; 3];
+        for (i, c) in self.perez.iter().enumerate() {
+            yxy[i] = self.zenith[i] * perez(c, cos_theta, gamma) / perez(c, 1.0, theta_s);
+        }
+        let (y, cx, cy) = (yxy[0], yxy[1], yxy[2]);
+        let xyz = Color::new(cx / cy * y, y, (1.0 - cx - cy) / cy * y);
+        let rgb = spectrum::xyz_to_linear_rgb(xyz);
+        let sky = Color::new(rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0));
+        self.scale * (sky + sun)
+    }
+
+    /// 在太阳圆盘内均匀采样一个方向，返回方向与辐亮度 / pdf
+    pub fn sample_sun(&self) -> (Vec3, Color) {
+        let cos_max = SUN_ANGULAR_RADIUS.cos();
+        let cos_theta = 1.0 - rtweekend::random_double() * (1.0 - cos_max);
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * rtweekend::random_double();
+        let local = Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+        let dir = Onb::new(self.sun_dir).transform(local);
+        let inv_pdf = 2.0 * PI * (1.0 - cos_max);
+        (dir, self.sun_radiance() * inv_pdf)
+    }
+
+    // 由太阳位置和浑浊度计算 Perez 系数、天顶值和太阳辐亮度
+    fn update(&mut self) {
+        let east = vec3::cross(self.north, Vec3::new(0.0, 1.0, 0.0));
+        let horizontal = self.azimuth.cos() * self.north + self.azimuth.sin() * east;
+        self.sun_dir = vec3::unit_vector(
+            self.elevation.cos() * horizontal + Vec3::new(0.0, self.elevation.sin(), 0.0),
+        );
+
+        let t = self.turbidity;
+        self.perez = [
+            [
+                0.1787 * t - 1.4630,
+                -0.3554 * t + 0.4275,
+                -0.0227 * t + 5.3251,
+                0.1206 * t - 2.5771,
+                -0.0670 * t + 0.3703,
+            ],
+            [
+                -0.0193 * t - 0.2592,
+                -0.0665 * t + 0.0008,
+                -0.0004 * t + 0.2125,
+                -0.0641 * t - 0.8989,
+                -0.0033 * t + 0.0452,
+            ],
+            [
+                -0.0167 * t - 0.2608,
+                -0.0950 * t + 0.0092,
+                -0.0079 * t + 0.2102,
+                -0.0441 * t - 1.6537,
+                -0.0109 * t + 0.0529,
+            ],
+        ];
+
+        // 天顶亮度（原式单位为 kcd/m²）与色度
+        let theta_s = PI / 2.0 - self.elevation.max(0.0);
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_s);
+        let zenith_y = ((4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192).max(0.0);
+        let chroma = |m: [[f64; 4]; 3]| {
+            let th = [theta_s.powi(3), theta_s.powi(2), theta_s, 1.0];
+            let tt = [t * t, t, 1.0];
+            (0..3)
+                .map(|i| tt[i] * (0..4).map(|j| m[i][j] * th[j]).sum::<f64>())
+                .sum::<f64>()
+        };
+        let zenith_x = chroma([
+            [0.00166, -0.00375, 0.00209, 0.0],
+            [-0.02903, 0.06377, -0.03202, 0.00394],
+            [0.11693, -0.21196, 0.06052, 0.25886],
+        ]);
+        let zenith_cy = chroma([
+            [0.00275, -0.00610, 0.00317, 0.0],
+            [-0.04214, 0.08970, -0.04153, 0.00516],
+            [0.15346, -0.26756, 0.06670, 0.26688],
+        ]);
+        self.zenith = [1000.0 * zenith_y, zenith_x, zenith_cy];
+
+        self.sun_radiance = if self.elevation < -SUN_ANGULAR_RADIUS {
+            Color::default()
+        } else {
+            sun_radiance(theta_s, t)
+        };
+    }
+}
+
+impl Light for PreethamSky {
+    fn sample(&self, _p: &Point3) -> Option<LightSample> {
+        let (direction, radiance) = self.sample_sun();
+        Some(LightSample {
+            direction,
+            distance: rtweekend::INFINITY,
+            radiance,
+        })
+    }
+}
+
+// Perez 天空亮度分布 F(θ, γ)
+fn perez(c: &[f64; 5], cos_theta: f64, gamma: f64) -> f64 {
+    let cos_gamma = gamma.cos();
+    (1.0 + c[0] * (c[1] / cos_theta).exp())
+        * (1.0 + c[2] * (c[3] * gamma).exp() + c[4] * cos_gamma * cos_gamma)
+}
+
+// 太阳圆盘经 Rayleigh 散射和气溶胶（Ångström 公式）衰减后的辐亮度，theta_s 为天顶角
+fn sun_radiance(theta_s: f64, turbidity: f64) -> Color {
+    // 相对大气质量（Kasten 公式，接近地平线时仍然有限）
+    let zenith_deg = theta_s.to_degrees();
+    let mass = 1.0 / (theta_s.cos() + 0.15 * (93.885 - zenith_deg).powf(-1.253));
+    let beta = 0.04608 * turbidity - 0.04586;
+
+    let mut xyz = Color::default();
+    let mut unattenuated_y = 0.0;
+    let mut lambda = LAMBDA_MIN + 2.5;
+    while lambda < LAMBDA_MAX {
+        let um = lambda / 1000.0;
+        let tau = 0.008735 * um.powf(-4.08) + beta * um.powf(-1.3);
+        let cmf = spectrum::cie_xyz(lambda);
+        let s = spectrum::planck(lambda, SUN_TEMPERATURE);
+        xyz += s * (-mass * tau).exp() * cmf;
+        unattenuated_y += s * cmf.y;
+        lambda += 5.0;
+    }
+    let rgb = spectrum::xyz_to_linear_rgb(xyz * (SUN_LUMINANCE / unattenuated_y));
+    Color::new(rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0))
+}
+
+/// 太阳的高度角与方位角（degrees，方位角从北向东计）。
+/// 赤纬用 Cooper 公式，时差用常见的三项近似，精度约 1°
+pub fn solar_position(
+    latitude: f64,
+    longitude: f64,
+    day_of_year: u32,
+    utc_hours: f64,
+) -> (f64, f64) {
+    let day = day_of_year as f64;
+    let declination = (23.44_f64.to_radians()) * (2.0 * PI * (284.0 + day) / 365.0).sin();
+    let b = 2.0 * PI * (day - 81.0) / 364.0;
+    let equation_of_time = 9.87 * (2.0 * b).sin() - 7.53 * b.cos() - 1.5 * b.sin(); // 分钟
+    let solar_time = utc_hours + longitude / 15.0 + equation_of_time / 60.0;
+    let hour_angle = (15.0 * (solar_time - 12.0)).to_radians();
+
+    let lat = latitude.to_radians();
+    let sin_elevation =
+        lat.sin() * declination.sin() + lat.cos() * declination.cos() * hour_angle.cos();
+    let elevation = sin_elevation.clamp(-1.0, 1.0).asin();
+    let cos_azimuth =
+        (declination.sin() - elevation.sin() * lat.sin()) / (elevation.cos() * lat.cos()).max(1e-9);
+    let mut azimuth = cos_azimuth.clamp(-1.0, 1.0).acos().to_degrees();
+    // 下午太阳在西边
+    if hour_angle.sin() > 0.0 {
+        azimuth = 360.0 - azimuth;
+    }
+    (elevation.to_degrees(), azimuth)
+}
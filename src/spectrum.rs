@@ -0,0 +1,293 @@
+//spectrum.rs
+// 光谱渲染：每条路径携带 4 个等间隔波长（hero wavelength），RGB 量按平滑基函数上采样为光谱，
+// 结果经 CIE 色匹配函数积分到 XYZ 后再换回 RGB
+use crate::color::Color;
+use crate::rtweekend;
+use std::sync::OnceLock;
+
+pub const LAMBDA_MIN: f64 = 380.0;
+pub const LAMBDA_MAX: f64 = 780.0;
+/// 每条路径携带的波长数
+pub const SPECTRUM_SAMPLES: usize = 4;
+
+/// 各采样波长上的光谱值
+pub type SampledSpectrum = [f64; SPECTRUM_SAMPLES];
+
+/// 一条路径的采样波长（nm），第 0 个为 hero 波长
+#[derive(Debug, Clone, Copy)]
+pub struct SampledWavelengths {
+    lambda: [f64; SPECTRUM_SAMPLES],
+    terminated: bool, // 是否只剩 hero 波长有效（经过色散事件后）
+}
+
+impl SampledWavelengths {
+    /// 在 [LAMBDA_MIN, LAMBDA_MAX] 内均匀采样 hero 波长，其余波长等间隔旋转排布
+    pub fn sample_uniform(u: f64) -> Self {
+        let range = LAMBDA_MAX - LAMBDA_MIN;
+        let hero = LAMBDA_MIN + u * range;
+        let step = range / SPECTRUM_SAMPLES as f64;
+        let mut lambda = [hero; SPECTRUM_SAMPLES];
+        for (i, l) in lambda.iter_mut().enumerate().skip(1) {
+            *l = LAMBDA_MIN + (hero - LAMBDA_MIN + i as f64 * step) % range;
+        }
+        Self {
+            lambda,
+            terminated: false,
+        }
+    }
+
+    pub fn random() -> Self {
+        Self::sample_uniform(rtweekend::random_double())
+    }
+
+    pub fn hero(&self) -> f64 {
+        self.lambda[0]
+    }
+
+    pub fn lambda(&self) -> &[f64; SPECTRUM_SAMPLES] {
+        &self.lambda
+    }
+
+    pub fn secondary_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    /// 色散事件后只保留 hero 波长：返回标记后的波长组
+    pub fn terminate_secondary(mut self) -> Self {
+        self.terminated = true;
+        self
+    }
+
+    /// 终止次波长后的估计值：hero 独自代表全部波长，其余清零以保持无偏
+    pub fn hero_only(values: SampledSpectrum) -> SampledSpectrum {
+        let mut out = [0.0; SPECTRUM_SAMPLES];
+        out[0] = values[0] * SPECTRUM_SAMPLES as f64;
+        out
+    }
+
+    /// 把 RGB 上采样为各采样波长上的值
+    pub fn upsample(&self, c: Color) -> SampledSpectrum {
+        self.lambda.map(|l| rgb_to_spectrum(c, l))
+    }
+
+    /// 积分到 XYZ 后换回 RGB
+    pub fn to_rgb(&self, values: &SampledSpectrum) -> Color {
+        // 均匀采样的 pdf 为 1 / (LAMBDA_MAX - LAMBDA_MIN)
+        let scale = (LAMBDA_MAX - LAMBDA_MIN) / SPECTRUM_SAMPLES as f64;
+        let mut xyz = Color::default();
+        for (&l, &v) in self.lambda.iter().zip(values) {
+            xyz += v * scale * cie_xyz(l);
+        }
+        xyz_to_rgb(xyz)
+    }
+}
+
+/// 把随波长变化的反射率 f(λ) 在可见光谱上按 samples 个分层波长积分并换为 RGB。
+/// 按同样积分的常数 1 归一化，因此恒为 1 的反射率得到白色
+pub fn reflectance_to_rgb(f: impl Fn(f64) -> f64, samples: usize) -> Color {
+    let step = (LAMBDA_MAX - LAMBDA_MIN) / samples as f64;
+    let mut xyz = Color::default();
+    let mut white = Color::default();
+    for i in 0..samples {
+        let lambda = LAMBDA_MIN + (i as f64 + 0.5) * step;
+        let cmf = cie_xyz(lambda);
+        xyz += f(lambda) * cmf;
+        white += cmf;
+    }
+    let rgb = xyz_to_rgb(xyz);
+    let white = xyz_to_rgb(white);
+    // 饱和的干涉色可能落在色域外，截断到 [0, 1]
+    Color::new(
+        (rgb.x / white.x).clamp(0.0, 1.0),
+        (rgb.y / white.y).clamp(0.0, 1.0),
+        (rgb.z / white.z).clamp(0.0, 1.0),
+    )
+}
+
+/// 普朗克定律给出的黑体光谱辐亮度（相对值，常数因子省略），lambda_nm 以 nm 计
+pub fn planck(lambda_nm: f64, kelvin: f64) -> f64 {
+    let l = lambda_nm * 1e-9;
+    1.0 / (l.powi(5) * ((1.438_776_9e-2 / (l * kelvin)).exp() - 1.0))
+}
+
+/// XYZ → 工作 RGB，Y 为亮度：(1,1,1) 对应 Y = 1，物理单位（cd/m²）的 XYZ 得到同单位的 RGB
+pub fn xyz_to_linear_rgb(xyz: Color) -> Color {
+    // xyz_to_rgb 以 1nm 步长积分，白色 (1,1,1) 对应的 Y 为 ∫ȳ dλ
+    static WHITE_Y: OnceLock<f64> = OnceLock::new();
+    let white_y = WHITE_Y.get_or_init(|| {
+        let mut y = 0.0;
+        let mut lambda = LAMBDA_MIN + 0.5;
+        while lambda < LAMBDA_MAX {
+            y += cie_xyz(lambda).y;
+            lambda += 1.0;
+        }
+        y
+    });
+    xyz_to_rgb(xyz * *white_y)
+}
+
+/// 温度为 kelvin 的黑体辐射颜色，归一化为亮度（Y）为 1，只表示色度
+pub fn blackbody(kelvin: f64) -> Color {
+    let mut xyz = Color::default();
+    let mut lambda = LAMBDA_MIN + 2.5;
+    while lambda < LAMBDA_MAX {
+        xyz += planck(lambda, kelvin) * cie_xyz(lambda);
+        lambda += 5.0;
+    }
+    if xyz.y <= 0.0 {
+        return Color::default();
+    }
+    let rgb = xyz_to_linear_rgb(xyz / xyz.y);
+    Color::new(rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0))
+}
+
+/// 随波长变化的折射率
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dispersion {
+    /// n(λ) = a + b / λ²，λ 以 μm 计
+    Cauchy { a: f64, b: f64 },
+    /// n²(λ) = 1 + Σ bᵢλ² / (λ² - cᵢ)，λ 以 μm 计
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Dispersion {
+    /// Schott N-BK7 冕牌玻璃
+    pub fn bk7() -> Self {
+        Dispersion::Sellmeier {
+            b: [1.039_612_12, 0.231_792_344, 1.010_469_45],
+            c: [0.006_000_698_67, 0.020_017_914_4, 103.560_653],
+        }
+    }
+
+    /// Schott SF11 重火石玻璃
+    pub fn sf11() -> Self {
+        Dispersion::Sellmeier {
+            b: [1.737_596_95, 0.313_747_346, 1.898_781_01],
+            c: [0.013_188_707, 0.062_306_814_2, 155.236_29],
+        }
+    }
+
+    /// 钻石
+    pub fn diamond() -> Self {
+        Dispersion::Sellmeier {
+            b: [0.3306, 4.3356, 0.0],
+            c: [0.175 * 0.175, 0.106 * 0.106, 0.0],
+        }
+    }
+
+    /// 波长 lambda_nm 处的折射率
+    pub fn ior(&self, lambda_nm: f64) -> f64 {
+        let l = lambda_nm * 1e-3;
+        let l2 = l * l;
+        match self {
+            Dispersion::Cauchy { a, b } => a + b / l2,
+            Dispersion::Sellmeier { b, c } => {
+                let sum: f64 = b.iter().zip(c).map(|(b, c)| b * l2 / (l2 - c)).sum();
+                (1.0 + sum).sqrt()
+            }
+        }
+    }
+
+    /// 夫琅禾费 d 线（587.6nm）处的折射率，用于 RGB 模式
+    pub fn ior_d(&self) -> f64 {
+        self.ior(587.6)
+    }
+}
+
+/// RGB 上采样后在波长 lambda 处的值
+pub fn rgb_to_spectrum(c: Color, lambda: f64) -> f64 {
+    let (r, g, b) = basis(lambda);
+    c.x * r + c.y * g + c.z * b
+}
+
+/// 把随波长变化的非辐射量（如折射率）按上采样基函数加权平均投影到 RGB 三个通道，
+/// 与 upsample 互为近似逆
+pub fn project_to_rgb(f: impl Fn(f64) -> f64) -> Color {
+    let mut sum = Color::default();
+    let mut weight = Color::default();
+    let mut lambda = LAMBDA_MIN + 0.5;
+    while lambda < LAMBDA_MAX {
+        let (r, g, b) = basis(lambda);
+        let basis = Color::new(r, g, b);
+        sum += f(lambda) * basis;
+        weight += basis;
+        lambda += 1.0;
+    }
+    Color::new(sum.x / weight.x, sum.y / weight.y, sum.z / weight.z)
+}
+
+// RGB 上采样基函数：三个平滑阶跃构成单位分解，白色 (1,1,1) 对应常数 1
+fn basis(lambda: f64) -> (f64, f64, f64) {
+    let b = 1.0 - smoothstep(475.0, 515.0, lambda);
+    let r = smoothstep(565.0, 605.0, lambda);
+    (r, 1.0 - r - b, b)
+}
+
+fn smoothstep(e0: f64, e1: f64, x: f64) -> f64 {
+    let t = ((x - e0) / (e1 - e0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// CIE 1931 色匹配函数的多瓣高斯拟合（Wyman, Sloan & Shirley 2013）
+pub fn cie_xyz(lambda: f64) -> Color {
+    let g = |mu: f64, s1: f64, s2: f64| {
+        let t = (lambda - mu) / if lambda < mu { s1 } else { s2 };
+        (-0.5 * t * t).exp()
+    };
+    Color::new(
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2),
+        0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1),
+        1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8),
+    )
+}
+
+// XYZ → 工作 RGB。矩阵取上采样基函数对应 XYZ 的逆，
+// 因此不经过色散的光谱能无损地换回原来的 RGB，白色保持为白色
+fn xyz_to_rgb(xyz: Color) -> Color {
+    static MATRIX: OnceLock<[[f64; 3]; 3]> = OnceLock::new();
+    let m = MATRIX.get_or_init(|| {
+        // 列 j 为第 j 个基函数的 XYZ
+        let mut a = [[0.0; 3]; 3];
+        let mut lambda = LAMBDA_MIN + 0.5;
+        while lambda < LAMBDA_MAX {
+            let (r, g, b) = basis(lambda);
+            let cmf = cie_xyz(lambda);
+            for (row, c) in a.iter_mut().zip([cmf.x, cmf.y, cmf.z]) {
+                row[0] += r * c;
+                row[1] += g * c;
+                row[2] += b * c;
+            }
+            lambda += 1.0;
+        }
+        invert3(&a)
+    });
+    Color::new(
+        m[0][0] * xyz.x + m[0][1] * xyz.y + m[0][2] * xyz.z,
+        m[1][0] * xyz.x + m[1][1] * xyz.y + m[1][2] * xyz.z,
+        m[2][0] * xyz.x + m[2][1] * xyz.y + m[2][2] * xyz.z,
+    )
+}
+
+fn invert3(a: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let cof =
+        |r0: usize, r1: usize, c0: usize, c1: usize| a[r0][c0] * a[r1][c1] - a[r0][c1] * a[r1][c0];
+    let det = a[0][0] * cof(1, 2, 1, 2) - a[0][1] * cof(1, 2, 0, 2) + a[0][2] * cof(1, 2, 0, 1);
+    let inv = 1.0 / det;
+    [
+        [
+            cof(1, 2, 1, 2) * inv,
+            -cof(0, 2, 1, 2) * inv,
+            cof(0, 1, 1, 2) * inv,
+        ],
+        [
+            -cof(1, 2, 0, 2) * inv,
+            cof(0, 2, 0, 2) * inv,
+            -cof(0, 1, 0, 2) * inv,
+        ],
+        [
+            cof(1, 2, 0, 1) * inv,
+            -cof(0, 2, 0, 1) * inv,
+            cof(0, 1, 0, 1) * inv,
+        ],
+    ]
+}
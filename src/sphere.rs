@@ -1,6 +1,6 @@
 // sphere.rs
 use crate::aabb::Aabb;
-use crate::hittable::{HitRecord, Hittable};
+use crate::hittable::{AlphaMask, HitRecord, Hittable};
 use crate::interval::Interval;
 use crate::material::{Lambertian, Material, Metal};
 use crate::ray::Ray;
@@ -17,6 +17,7 @@ pub struct Sphere {
     is_moving: bool,
     center_vec: Vec3,
     bbox: Aabb,
+    alpha: Option<AlphaMask>,
 }
 
 impl Sphere {
@@ -36,6 +37,7 @@ impl Sphere {
                 center1 - Vec3::new(radius, radius, radius),
                 center1 + Vec3::new(radius, radius, radius),
             ),
+            alpha: None,
         }
     }
 
@@ -55,9 +57,16 @@ impl Sphere {
             is_moving: true,
             center_vec: center2 - center1,
             bbox: Aabb::new_boxes(&box1, &box2),
+            alpha: None,
         }
     }
 
+    /// 设置透明度遮罩，被遮罩的位置光线直接穿过（可以看到球的内侧）
+    pub fn with_alpha(mut self, alpha: AlphaMask) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
     fn center(&self, time: f64) -> Point3 {
         if self.is_moving {
             self.center1 + time * self.center_vec
@@ -89,14 +98,23 @@ impl Hittable for Sphere {
         }
 
         let sqrtd = discriminant.sqrt();
-        // 寻找最近的且在有效区间内的根
-        let mut root = (h - sqrtd) / a;
-        if !ray_t.surrounds(root) {
-            root = (h + sqrtd) / a;
-            if !ray_t.surrounds(root) {
-                return false;
-            }
-        }
+        // 寻找最近的且在有效区间内、未被透明度遮罩镂空的根
+        let Some(root) = [(h - sqrtd) / a, (h + sqrtd) / a]
+            .into_iter()
+            .find(|&root| {
+                if !ray_t.surrounds(root) {
+                    return false;
+                }
+                let Some(alpha) = &self.alpha else {
+                    return true;
+                };
+                let p = r.at(root);
+                let (u, v) = Sphere::get_sphere_uv((p - cur_center) / self.radius);
+                alpha.is_opaque(u, v, &p)
+            })
+        else {
+            return false;
+        };
 
         // 填充命中记录
         rec.t = root;
@@ -104,6 +122,9 @@ impl Hittable for Sphere {
         let outward_normal = (rec.p - cur_center) / self.radius;
         rec.set_face_normal(r, outward_normal);
         (rec.u, rec.v) = Sphere::get_sphere_uv(outward_normal);
+        // dp/du 沿纬线方向，dp/dv 沿经线指向北极
+        let tangent = Vec3::new(outward_normal.z, 0.0, -outward_normal.x);
+        rec.set_tangents(tangent, vec3::cross(outward_normal, tangent));
         rec.mat = Arc::clone(&self.mat);
         true
     }
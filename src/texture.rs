@@ -8,6 +8,11 @@ use std::sync::Arc;
 
 pub trait Texture: Send + Sync {
     fn value(&self, u: f64, v: f64, p: &Point3) -> Color;
+
+    /// 不透明度 [0, 1]，默认完全不透明
+    fn alpha(&self, _u: f64, _v: f64, _p: &Point3) -> f64 {
+        1.0
+    }
 }
 
 pub struct SolidColor {
@@ -32,6 +37,61 @@ impl Texture for SolidColor {
     }
 }
 
+/// 纹理乘以颜色因子（如 glTF 中 factor × texture），alpha 同样乘以 alpha 因子
+pub struct ScaledTexture {
+    texture: Arc<dyn Texture>,
+    scale: Color,
+    alpha_scale: f64,
+}
+
+impl ScaledTexture {
+    pub fn new(texture: Arc<dyn Texture>, scale: Color) -> Self {
+        Self {
+            texture,
+            scale,
+            alpha_scale: 1.0,
+        }
+    }
+    pub fn with_alpha(mut self, alpha_scale: f64) -> Self {
+        self.alpha_scale = alpha_scale;
+        self
+    }
+}
+
+impl Texture for ScaledTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        self.texture.value(u, v, p) * self.scale
+    }
+    fn alpha(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        self.texture.alpha(u, v, p) * self.alpha_scale
+    }
+}
+
+/// 取纹理的单个通道（0:R, 1:G, 2:B）乘以 scale 作为灰度值，
+/// 用于 metallicRoughness 这类打包在不同通道中的标量贴图
+pub struct ChannelTexture {
+    texture: Arc<dyn Texture>,
+    channel: usize,
+    scale: f64,
+}
+
+impl ChannelTexture {
+    pub fn new(texture: Arc<dyn Texture>, channel: usize, scale: f64) -> Self {
+        Self {
+            texture,
+            channel: channel.min(2),
+            scale,
+        }
+    }
+}
+
+impl Texture for ChannelTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        let c = self.scale * self.texture.value(u, v, p)[self.channel];
+        Color::new(c, c, c)
+    }
+}
+
 pub struct CheckerTexture {
     inv_scale: f64,
     even: Arc<dyn Texture>,
@@ -85,11 +145,9 @@ impl ImageTexture {
         Self { image }
     }
 }
-impl Texture for ImageTexture {
-    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
-        if self.image.height <= 0 {
-            return Color::new(0.0, 1.0, 1.0);
-        }
+impl ImageTexture {
+    // 纹理坐标对应的像素位置，v 向上
+    fn texel(&self, u: f64, v: f64) -> (i32, i32) {
         let u = u.clamp(0.0, 1.0);
         let v = 1.0 - v.clamp(0.0, 1.0);
         let i = (u * self.image.width as f64) as usize;
@@ -97,8 +155,16 @@ impl Texture for ImageTexture {
 
         let i = i.min(self.image.width - 1);
         let j = j.min(self.image.height - 1);
-
-        let pixel = self.image.pixel_data(i as i32, j as i32);
+        (i as i32, j as i32)
+    }
+}
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        if self.image.height <= 0 {
+            return Color::new(0.0, 1.0, 1.0);
+        }
+        let (i, j) = self.texel(u, v);
+        let pixel = self.image.pixel_data(i, j);
 
         let color_scale = 1.0 / 255.0;
         Color::new(
@@ -107,6 +173,13 @@ impl Texture for ImageTexture {
             color_scale * pixel[2] as f64,
         )
     }
+    fn alpha(&self, u: f64, v: f64, _p: &Point3) -> f64 {
+        if self.image.height == 0 {
+            return 1.0;
+        }
+        let (i, j) = self.texel(u, v);
+        self.image.alpha_data(i, j) as f64 / 255.0
+    }
 }
 
 pub struct NoiseTexture {
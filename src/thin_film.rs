@@ -0,0 +1,180 @@
+//thin_film.rs
+// 薄膜干涉：基底上覆盖一层厚度与光波长相当的透明薄膜（肥皂泡、油膜、氧化层），
+// 膜上下表面的反射光相互干涉，反射率随波长和角度变化产生虹彩
+use crate::color::Color;
+use crate::hittable::HitRecord;
+use crate::rtweekend;
+use crate::spectrum;
+use crate::texture::{SolidColor, Texture};
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+use std::sync::Arc;
+
+/// 复数，用于复折射率和菲涅尔振幅系数
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    pub fn real(re: f64) -> Self {
+        Self { re, im: 0.0 }
+    }
+
+    pub fn norm_sqr(&self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    /// 主值平方根（实部非负）
+    pub fn sqrt(&self) -> Self {
+        let r = self.norm_sqr().sqrt();
+        let re = (0.5 * (r + self.re)).max(0.0).sqrt();
+        let im = (0.5 * (r - self.re)).max(0.0).sqrt();
+        Self::new(re, if self.im < 0.0 { -im } else { im })
+    }
+
+    /// e^{i·z}
+    pub fn exp_i(&self) -> Self {
+        let m = (-self.im).exp();
+        Self::new(m * self.re.cos(), m * self.re.sin())
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+    fn add(self, o: Self) -> Self {
+        Self::new(self.re + o.re, self.im + o.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+    fn sub(self, o: Self) -> Self {
+        Self::new(self.re - o.re, self.im - o.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+    fn mul(self, o: Self) -> Self {
+        Self::new(
+            self.re * o.re - self.im * o.im,
+            self.re * o.im + self.im * o.re,
+        )
+    }
+}
+
+impl Mul<f64> for Complex {
+    type Output = Self;
+    fn mul(self, s: f64) -> Self {
+        Self::new(self.re * s, self.im * s)
+    }
+}
+
+impl Div for Complex {
+    type Output = Self;
+    fn div(self, o: Self) -> Self {
+        let d = o.norm_sqr();
+        Self::new(
+            (self.re * o.re + self.im * o.im) / d,
+            (self.im * o.re - self.re * o.im) / d,
+        )
+    }
+}
+
+/// 表面上的薄膜涂层，thickness 以纳米计（取纹理 x 通道）
+#[derive(Clone)]
+pub struct ThinFilm {
+    pub thickness: Arc<dyn Texture + Send + Sync>,
+    pub ior: f64,
+}
+
+impl fmt::Debug for ThinFilm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThinFilm").field("ior", &self.ior).finish()
+    }
+}
+
+impl ThinFilm {
+    // RGB 模式下积分反射光谱时使用的波长数
+    const RGB_SAMPLES: usize = 16;
+
+    pub fn new(thickness_nm: f64, ior: f64) -> Self {
+        let t = Color::new(thickness_nm, thickness_nm, thickness_nm);
+        Self::new_from_texture(Arc::new(SolidColor::new(t)), ior)
+    }
+
+    pub fn new_from_texture(thickness: Arc<dyn Texture + Send + Sync>, ior: f64) -> Self {
+        Self { thickness, ior }
+    }
+
+    /// 入射介质折射率为 n_incident、基底复折射率随波长由 substrate 给出时的反射率。
+    /// 光谱模式（wavelength 为 Some）只计算该波长，结果为灰色；
+    /// RGB 模式对整个可见光谱积分后换为 RGB
+    pub fn reflectance(
+        &self,
+        rec: &HitRecord,
+        cos_i: f64,
+        n_incident: f64,
+        substrate: impl Fn(f64) -> Complex,
+        wavelength: Option<f64>,
+    ) -> Color {
+        let d = self.thickness.value(rec.u, rec.v, &rec.p).x.max(0.0);
+        let r = |lambda: f64| {
+            airy_reflectance(cos_i, n_incident, self.ior, substrate(lambda), d, lambda)
+        };
+        match wavelength {
+            Some(lambda) => {
+                let r = r(lambda);
+                Color::new(r, r, r)
+            }
+            None => spectrum::reflectance_to_rgb(r, Self::RGB_SAMPLES),
+        }
+    }
+}
+
+/// 单层薄膜的 Airy 反射率（s、p 偏振平均）：入射介质 n1、薄膜 n2（厚度 d nm）、基底 n3
+pub fn airy_reflectance(cos_i: f64, n1: f64, n2: f64, n3: Complex, d: f64, lambda: f64) -> f64 {
+    let n1c = Complex::real(n1);
+    let n2c = Complex::real(n2);
+    let sin2 = (1.0 - cos_i * cos_i).max(0.0);
+    // 各层中的折射角余弦（可能为复数：全反射或吸收介质）
+    let cos_in = |n: Complex| {
+        let s = Complex::real(n1 * n1 * sin2) / (n * n);
+        (Complex::real(1.0) - s).sqrt()
+    };
+    let c1 = Complex::real(cos_i.clamp(0.0, 1.0));
+    let c2 = cos_in(n2c);
+    let c3 = cos_in(n3);
+
+    let rs = |na: Complex, ca: Complex, nb: Complex, cb: Complex| {
+        (na * ca - nb * cb) / (na * ca + nb * cb)
+    };
+    let rp = |na: Complex, ca: Complex, nb: Complex, cb: Complex| {
+        (nb * ca - na * cb) / (nb * ca + na * cb)
+    };
+
+    // 膜内往返的相位差
+    let phase = (n2c * c2 * (4.0 * rtweekend::PI * d / lambda)).exp_i();
+    let airy = |r12: Complex, r23: Complex| {
+        let r = (r12 + r23 * phase) / (Complex::real(1.0) + r12 * r23 * phase);
+        r.norm_sqr().min(1.0)
+    };
+    0.5 * (airy(rs(n1c, c1, n2c, c2), rs(n2c, c2, n3, c3))
+        + airy(rp(n1c, c1, n2c, c2), rp(n2c, c2, n3, c3)))
+}
+
+/// 由 F0 和边缘色调换算导体的复折射率（Gulbrandsen 2014），用于 Schlick 金属上的薄膜
+pub fn artist_friendly_conductor(r: f64, edge_tint: f64) -> Complex {
+    let r = r.clamp(0.0, 0.99);
+    let g = edge_tint.clamp(0.0, 1.0);
+    let sr = r.sqrt();
+    let n = g * (1.0 - r) / (1.0 + r) + (1.0 - g) * (1.0 + sr) / (1.0 - sr);
+    let k2 = ((n + 1.0).powi(2) * r - (n - 1.0).powi(2)) / (1.0 - r);
+    Complex::new(n, k2.max(0.0).sqrt())
+}
@@ -1,6 +1,6 @@
 // triangle.rs
 use crate::aabb::Aabb;
-use crate::hittable::{HitRecord, Hittable};
+use crate::hittable::{AlphaMask, HitRecord, Hittable};
 use crate::interval::Interval;
 use crate::material::Material;
 use crate::ray::Ray;
@@ -17,6 +17,11 @@ pub struct Triangle {
     // 我们可以预计算法线，但如果模型带有法线数据，则情况更复杂
     // 为简单起见，我们先自己计算
     normal: Vec3,
+    // 顶点纹理坐标及由其推导的切线 dp/du、副切线 dp/dv
+    uv: [(f64, f64); 3],
+    tangent: Vec3,
+    bitangent: Vec3,
+    alpha: Option<AlphaMask>,
 }
 
 impl Triangle {
@@ -33,8 +38,34 @@ impl Triangle {
             mat,
             bbox: final_bbox,
             normal,
+            // 默认纹理坐标即重心坐标
+            uv: [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)],
+            tangent: edge1,
+            bitangent: edge2,
+            alpha: None,
         }
     }
+
+    /// 设置透明度遮罩，被遮罩的位置光线直接穿过
+    pub fn with_alpha(mut self, alpha: AlphaMask) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
+    /// 设置三个顶点的纹理坐标，并据此求出切线坐标系
+    pub fn with_uvs(mut self, uv0: (f64, f64), uv1: (f64, f64), uv2: (f64, f64)) -> Self {
+        self.uv = [uv0, uv1, uv2];
+        let (du1, dv1) = (uv1.0 - uv0.0, uv1.1 - uv0.1);
+        let (du2, dv2) = (uv2.0 - uv0.0, uv2.1 - uv0.1);
+        let det = du1 * dv2 - du2 * dv1;
+        if det.abs() > 1e-12 {
+            // 解 edge = dp/du · du + dp/dv · dv
+            let inv = 1.0 / det;
+            self.tangent = (dv2 * self.edge1 - dv1 * self.edge2) * inv;
+            self.bitangent = (du1 * self.edge2 - du2 * self.edge1) * inv;
+        }
+        self
+    }
 }
 
 impl Hittable for Triangle {
@@ -71,15 +102,26 @@ impl Hittable for Triangle {
             return false;
         }
 
+        // 由重心坐标插值顶点纹理坐标
+        let w = 1.0 - u - v;
+        let tex_u = w * self.uv[0].0 + u * self.uv[1].0 + v * self.uv[2].0;
+        let tex_v = w * self.uv[0].1 + u * self.uv[1].1 + v * self.uv[2].1;
+        let p = r.at(t);
+        // 必须在写入 rec 之前判断，rec 中可能保存着更远处的有效命中
+        if let Some(alpha) = &self.alpha
+            && !alpha.is_opaque(tex_u, tex_v, &p)
+        {
+            return false;
+        }
+
         rec.t = t;
-        rec.p = r.at(t);
+        rec.p = p;
         rec.mat = Arc::clone(&self.mat);
         // 这里我们暂时使用几何法线，后续可以支持平滑着色的顶点法线
         rec.set_face_normal(r, self.normal);
-
-        // 你也可以选择在这里计算并填充纹理坐标 u, v
-        rec.u = u;
-        rec.v = v;
+        rec.u = tex_u;
+        rec.v = tex_v;
+        rec.set_tangents(self.tangent, self.bitangent);
 
         true
     }
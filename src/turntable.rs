@@ -0,0 +1,212 @@
+//turntable.rs
+use crate::aabb::Aabb;
+use crate::animation::frame_path;
+use crate::camera::{write_ppm, Camera};
+use crate::color::Color;
+use crate::hittable::Hittable;
+use crate::hittable_list::HittableList;
+use crate::material::{DiffuseLight, Lambertian, Material};
+use crate::quad::Quad;
+use crate::rtweekend;
+use crate::vec3;
+use crate::vec3::{Point3, Vec3};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// 转台预览：自动按包围盒取景，在固定仰角上环绕物体渲染 N 帧，并拼接成联系表
+#[derive(Debug, Clone)]
+pub struct Turntable {
+    pub frames: usize,           // 一圈的帧数
+    pub elevation: f64,          // 相机仰角（degrees）
+    pub start_azimuth: f64,      // 第一帧的方位角（degrees）
+    pub margin: f64,             // 取景留白倍数，1.0 表示包围球恰好贴边
+    pub studio_lights: bool,     // 是否添加默认的三点布光和地面
+    pub sheet_columns: usize,    // 联系表列数，0 表示自动
+    pub sheet_downsample: usize, // 联系表中缩略图的缩小倍数
+}
+
+impl Default for Turntable {
+    fn default() -> Self {
+        Self {
+            frames: 36,
+            elevation: 20.0,
+            start_azimuth: 0.0,
+            margin: 1.15,
+            studio_lights: true,
+            sheet_columns: 0,
+            sheet_downsample: 2,
+        }
+    }
+}
+
+impl Turntable {
+    pub fn new(frames: usize, elevation: f64) -> Self {
+        Self {
+            frames,
+            elevation,
+            ..Default::default()
+        }
+    }
+
+    /// 渲染转台序列 `{prefix}_{frame:04}.ppm` 和联系表 `{prefix}_contact.ppm`，
+    /// cam 提供分辨率、采样数等渲染参数，视角参数会被覆盖
+    pub fn render(
+        &self,
+        object: Arc<dyn Hittable + Send + Sync>,
+        cam: &Camera,
+        prefix: &str,
+    ) -> io::Result<Vec<PathBuf>> {
+        if let Some(dir) = Path::new(prefix).parent()
+            && !dir.as_os_str().is_empty()
+        {
+            fs::create_dir_all(dir)?;
+        }
+
+        let bbox = object.bounding_box();
+        let center = (bbox.min() + bbox.max()) * 0.5;
+        let radius = 0.5 * (bbox.max() - bbox.min()).length();
+
+        let mut written = Vec::with_capacity(self.frames + 1);
+        let mut thumbnails = Vec::with_capacity(self.frames);
+        for frame in 0..self.frames {
+            let azimuth = self.start_azimuth + 360.0 * frame as f64 / self.frames as f64;
+
+            let mut world = HittableList::default();
+            world.add(Arc::clone(&object));
+            if self.studio_lights {
+                add_studio_rig(&mut world, center, radius, bbox.y.min, azimuth);
+            }
+
+            let mut frame_cam = cam.clone();
+            self.frame_camera(&mut frame_cam, &bbox, azimuth);
+
+            eprintln!("[TURNTABLE] Rendering frame {}/{}", frame + 1, self.frames);
+            let image = frame_cam.render_image(&world);
+            let path = frame_path(prefix, frame as i32);
+            let mut out = BufWriter::new(File::create(&path)?);
+            write_ppm(&image, &mut out)?;
+            out.flush()?;
+            written.push(path);
+
+            thumbnails.push(downsample(&image, self.sheet_downsample.max(1)));
+        }
+
+        let sheet_path = PathBuf::from(format!("{}_contact.ppm", prefix));
+        let mut out = BufWriter::new(File::create(&sheet_path)?);
+        write_ppm(&contact_sheet(&thumbnails, self.sheet_columns), &mut out)?;
+        out.flush()?;
+        written.push(sheet_path);
+        Ok(written)
+    }
+
+    // 让相机在给定方位角看向物体，再按包围盒自动取景
+    fn frame_camera(&self, cam: &mut Camera, bbox: &Aabb, azimuth: f64) {
+        let el = rtweekend::degrees_to_radians(self.elevation);
+        let az = rtweekend::degrees_to_radians(azimuth);
+        let dir = Vec3::new(el.cos() * az.sin(), el.sin(), el.cos() * az.cos());
+
+        cam.lookat = Point3::default();
+        cam.lookfrom = dir;
+        cam.vup = Vec3::new(0.0, 1.0, 0.0);
+        cam.fit_to_aabb(bbox, self.margin);
+    }
+}
+
+// 默认影棚布光：主光、辅光、轮廓光随相机方位角一起旋转，外加一块中灰地面
+fn add_studio_rig(world: &mut HittableList, center: Point3, radius: f64, floor: f64, azimuth: f64) {
+    let radius = radius.max(1e-3);
+    let lights = [
+        // (相对方位角, 仰角, 距离倍数, 边长倍数, 颜色)
+        (-45.0, 35.0, 3.0, 1.5, Color::new(6.0, 5.7, 5.2)),
+        (60.0, 10.0, 3.5, 2.0, Color::new(1.5, 1.6, 1.8)),
+        (170.0, 45.0, 3.0, 1.0, Color::new(5.0, 5.0, 5.0)),
+    ];
+    for (rel_az, el, dist, size, color) in lights {
+        let az = rtweekend::degrees_to_radians(azimuth + rel_az);
+        let el = rtweekend::degrees_to_radians(el);
+        let dir = Vec3::new(el.cos() * az.sin(), el.sin(), el.cos() * az.cos());
+        let pos = center + dist * radius * dir;
+        world.add(Arc::new(facing_quad(
+            pos,
+            center,
+            size * radius,
+            Arc::new(DiffuseLight::new_from_color(color)),
+        )));
+    }
+
+    let ground = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    let extent = 20.0 * radius;
+    world.add(Arc::new(Quad::new(
+        Point3::new(center.x - extent, floor, center.z + extent),
+        Vec3::new(2.0 * extent, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -2.0 * extent),
+        ground,
+    )));
+}
+
+// 以 pos 为中心、法线朝向 target 的正方形面片
+fn facing_quad(
+    pos: Point3,
+    target: Point3,
+    size: f64,
+    mat: Arc<dyn Material + Send + Sync>,
+) -> Quad {
+    let n = vec3::unit_vector(target - pos);
+    let up = if n.y.abs() > 0.99 {
+        Vec3::new(1.0, 0.0, 0.0)
+    } else {
+        Vec3::new(0.0, 1.0, 0.0)
+    };
+    let a = vec3::unit_vector(vec3::cross(up, n));
+    let b = vec3::cross(n, a);
+    let q = pos - 0.5 * size * (a + b);
+    Quad::new(q, size * a, size * b, mat)
+}
+
+// 按整数倍做盒式缩小
+fn downsample(image: &[Vec<Color>], factor: usize) -> Vec<Vec<Color>> {
+    let height = image.len() / factor;
+    let width = image.first().map_or(0, |row| row.len()) / factor;
+    let weight = 1.0 / (factor * factor) as f64;
+    (0..height)
+        .map(|j| {
+            (0..width)
+                .map(|i| {
+                    let mut sum = Color::default();
+                    for row in &image[j * factor..(j + 1) * factor] {
+                        for pixel in &row[i * factor..(i + 1) * factor] {
+                            sum += *pixel;
+                        }
+                    }
+                    sum * weight
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// 将缩略图按行优先拼接成网格
+fn contact_sheet(thumbnails: &[Vec<Vec<Color>>], columns: usize) -> Vec<Vec<Color>> {
+    let Some(first) = thumbnails.first() else {
+        return Vec::new();
+    };
+    let columns = if columns == 0 {
+        (thumbnails.len() as f64).sqrt().ceil() as usize
+    } else {
+        columns
+    };
+    let rows = thumbnails.len().div_ceil(columns);
+    let th = first.len();
+    let tw = first.first().map_or(0, |row| row.len());
+
+    let mut sheet = vec![vec![Color::default(); columns * tw]; rows * th];
+    for (idx, thumb) in thumbnails.iter().enumerate() {
+        let (x0, y0) = ((idx % columns) * tw, (idx / columns) * th);
+        for (j, row) in thumb.iter().enumerate() {
+            sheet[y0 + j][x0..x0 + tw].copy_from_slice(row);
+        }
+    }
+    sheet
+}
@@ -0,0 +1,371 @@
+//voxel_grid.rs
+// 稠密体素网格（烟雾模拟的密度、温度等标量场），三线性插值，
+// 配套一个粗粒度的 majorant 网格，使 delta tracking 在稀疏区域可以大步跳过。
+//
+// 支持两种文件格式，数值均为小端 f32，x 变化最快：
+// - raw：无文件头，恰好 nx·ny·nz 个值，分辨率由调用方给出
+// - 稀疏块格式（.vxg）：
+//     magic "VXG1"，u32 nx, ny, nz，u32 块边长 B，u32 块数 N，
+//     之后 N 个块，每块为 u32 bx, by, bz（块坐标）加 B³ 个值；未出现的块视为 0
+use crate::color::Color;
+use crate::density::DensityField;
+use crate::ray::Ray;
+use crate::spectrum;
+use crate::texture::Texture;
+use crate::vec3::{Point3, Vec3};
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+use std::sync::Arc;
+
+pub struct VoxelGrid {
+    res: [usize; 3],
+    data: Vec<f32>,
+    min: Point3, // 网格在局部空间中占据的包围盒，默认单位立方体
+    max: Point3,
+    max_value: f64,
+    majorant_res: [usize; 3],
+    majorant: Vec<f32>, // 每个 majorant 单元内（含插值会用到的相邻体素）的最大值
+}
+
+impl VoxelGrid {
+    // majorant 单元的边长（体素数）
+    const MAJORANT_CELL: usize = 8;
+    // .vxg 头部允许的最大体素数（4 GiB 的 f32），稀疏文件很小也会按分辨率分配整个网格
+    const MAX_VOXELS: usize = 1 << 30;
+
+    /// data 按 x 最快、z 最慢排列，长度必须为 nx·ny·nz
+    pub fn new(res: [usize; 3], data: Vec<f32>) -> Self {
+        assert_eq!(data.len(), res[0] * res[1] * res[2], "voxel count mismatch");
+        let mut grid = Self {
+            res,
+            data,
+            min: Point3::new(0.0, 0.0, 0.0),
+            max: Point3::new(1.0, 1.0, 1.0),
+            max_value: 0.0,
+            majorant_res: [0; 3],
+            majorant: Vec::new(),
+        };
+        grid.build_majorant();
+        grid
+    }
+
+    /// 读取无文件头的 raw 网格
+    pub fn load_raw(path: &str, res: [usize; 3]) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        let count = res[0] * res[1] * res[2];
+        if bytes.len() != count * 4 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("{path}: expected {} bytes, got {}", count * 4, bytes.len()),
+            ));
+        }
+        Ok(Self::new(res, read_f32s(&bytes)))
+    }
+
+    /// 读取稀疏块格式（.vxg）
+    pub fn load_sparse(path: &str) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        let invalid = |msg: &str| Error::new(ErrorKind::InvalidData, format!("{path}: {msg}"));
+        if bytes.len() < 24 || &bytes[0..4] != b"VXG1" {
+            return Err(invalid("not a VXG1 file"));
+        }
+        let header = read_u32s(&bytes[4..24]);
+        let res = [header[0], header[1], header[2]];
+        let (block, count) = (header[3], header[4]);
+        // 头部的值来自文件，乘积可能溢出
+        let block_bytes = block
+            .checked_mul(block)
+            .and_then(|n| n.checked_mul(block))
+            .and_then(|n| n.checked_mul(4))
+            .and_then(|n| n.checked_add(12))
+            .ok_or_else(|| invalid("block size too large"))?;
+        let total = count
+            .checked_mul(block_bytes)
+            .and_then(|n| n.checked_add(24))
+            .ok_or_else(|| invalid("block count too large"))?;
+        if block == 0 || bytes.len() != total {
+            return Err(invalid("truncated block data"));
+        }
+        let voxels = res[0]
+            .checked_mul(res[1])
+            .and_then(|n| n.checked_mul(res[2]))
+            .filter(|&n| n <= Self::MAX_VOXELS)
+            .ok_or_else(|| invalid("resolution too large"))?;
+
+        let mut data = vec![0.0; voxels];
+        for chunk in bytes[24..].chunks_exact(block_bytes) {
+            let coords = read_u32s(&chunk[..12]);
+            // 块原点超出网格（或溢出）时整块都在网格外
+            let Some(origin) = coords
+                .iter()
+                .zip(res)
+                .map(|(&c, n)| c.checked_mul(block).filter(|&o| o < n))
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            let values = read_f32s(&chunk[12..]);
+            for (i, v) in values.into_iter().enumerate() {
+                let x = origin[0] + i % block;
+                let y = origin[1] + (i / block) % block;
+                let z = origin[2] + i / (block * block);
+                if x < res[0] && y < res[1] && z < res[2] {
+                    data[x + res[0] * (y + res[1] * z)] = v;
+                }
+            }
+        }
+        Ok(Self::new(res, data))
+    }
+
+    /// 设置网格在局部空间中的包围盒；旋转等变换可再套 RotateY、Translate 等
+    pub fn with_bounds(mut self, min: Point3, max: Point3) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    pub fn bounds(&self) -> (Point3, Point3) {
+        (self.min, self.max)
+    }
+
+    fn voxel(&self, x: i64, y: i64, z: i64) -> f64 {
+        let [nx, ny, nz] = self.res.map(|n| n as i64);
+        if x < 0 || y < 0 || z < 0 || x >= nx || y >= ny || z >= nz {
+            return 0.0;
+        }
+        self.data[(x + nx * (y + ny * z)) as usize] as f64
+    }
+
+    // 局部坐标 → 连续体素坐标（体素中心位于整数 + 0.5）
+    fn to_voxel(&self, p: &Point3) -> Vec3 {
+        let mut g = Vec3::default();
+        for a in 0..3 {
+            g[a] = (p[a] - self.min[a]) / (self.max[a] - self.min[a]) * self.res[a] as f64;
+        }
+        g
+    }
+
+    /// p 处的三线性插值，网格外为 0
+    pub fn lookup(&self, p: &Point3) -> f64 {
+        let g = self.to_voxel(p);
+        for a in 0..3 {
+            if g[a] < 0.0 || g[a] > self.res[a] as f64 {
+                return 0.0;
+            }
+        }
+        let g = g - Vec3::new(0.5, 0.5, 0.5);
+        let (x, y, z) = (g.x.floor(), g.y.floor(), g.z.floor());
+        let (fx, fy, fz) = (g.x - x, g.y - y, g.z - z);
+        let (x, y, z) = (x as i64, y as i64, z as i64);
+
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+        let c00 = lerp(self.voxel(x, y, z), self.voxel(x + 1, y, z), fx);
+        let c10 = lerp(self.voxel(x, y + 1, z), self.voxel(x + 1, y + 1, z), fx);
+        let c01 = lerp(self.voxel(x, y, z + 1), self.voxel(x + 1, y, z + 1), fx);
+        let c11 = lerp(
+            self.voxel(x, y + 1, z + 1),
+            self.voxel(x + 1, y + 1, z + 1),
+            fx,
+        );
+        lerp(lerp(c00, c10, fy), lerp(c01, c11, fy), fz)
+    }
+
+    fn build_majorant(&mut self) {
+        let cell = Self::MAJORANT_CELL;
+        self.majorant_res = self.res.map(|n| n.div_ceil(cell).max(1));
+        let [mx, my, mz] = self.majorant_res;
+        self.majorant = vec![0.0; mx * my * mz];
+        self.max_value = 0.0;
+
+        // 单元内任一点的插值会用到单元外一圈的体素
+        for cz in 0..mz {
+            for cy in 0..my {
+                for cx in 0..mx {
+                    let range = |c: usize| (c * cell) as i64 - 1..=((c + 1) * cell) as i64;
+                    let mut m = 0.0_f64;
+                    for z in range(cz) {
+                        for y in range(cy) {
+                            for x in range(cx) {
+                                m = m.max(self.voxel(x, y, z));
+                            }
+                        }
+                    }
+                    self.majorant[cx + mx * (cy + my * cz)] = m as f32;
+                    self.max_value = self.max_value.max(m);
+                }
+            }
+        }
+    }
+
+    /// 从 r.at(t) 起所在 majorant 单元的上界，以及光线离开该单元时的参数 t。
+    /// 网格外返回 0，以及光线进入网格的位置
+    pub fn majorant(&self, r: &Ray, t: f64) -> (f64, f64) {
+        let dir = r.direction();
+        let g = self.to_voxel(&r.at(t));
+        let cell = Self::MAJORANT_CELL as f64;
+
+        let mut inside = true;
+        let mut t_exit = f64::INFINITY;
+        let (mut box_enter, mut box_exit) = (f64::NEG_INFINITY, f64::INFINITY);
+        let mut index = [0usize; 3];
+        for a in 0..3 {
+            let voxel_size = (self.max[a] - self.min[a]) / self.res[a] as f64;
+            // 恰好落在单元边界上时按行进方向归入下一个单元
+            let nudge = if dir[a] > 0.0 { 1e-7 } else { -1e-7 };
+            let c = ((g[a] + nudge) / cell).floor();
+            if c < 0.0 || c >= self.majorant_res[a] as f64 {
+                inside = false;
+            }
+            if dir[a] != 0.0 {
+                // 当前单元在该轴上的出口平面
+                let plane = if dir[a] > 0.0 { c + 1.0 } else { c };
+                let plane = self.min[a] + plane * cell * voxel_size;
+                t_exit = t_exit.min((plane - r.origin()[a]) / dir[a]);
+
+                // 光线与整个网格包围盒的交点区间
+                let t0 = (self.min[a] - r.origin()[a]) / dir[a];
+                let t1 = (self.max[a] - r.origin()[a]) / dir[a];
+                box_enter = box_enter.max(t0.min(t1));
+                box_exit = box_exit.min(t0.max(t1));
+            } else if r.origin()[a] < self.min[a] || r.origin()[a] > self.max[a] {
+                box_exit = f64::NEG_INFINITY;
+            }
+            index[a] = c.max(0.0) as usize;
+        }
+
+        if !inside {
+            // 之后不再进入网格时直接跳到无穷远；恰在入口附近时只前进一点
+            if box_enter >= box_exit || box_exit <= t {
+                return (0.0, f64::INFINITY);
+            }
+            return (0.0, box_enter.max(t + 1e-9));
+        }
+        let [mx, my, _] = self.majorant_res;
+        let m = self.majorant[index[0] + mx * (index[1] + my * index[2])] as f64;
+        // 数值误差可能让出口不在前方，保证至少前进一点
+        (m, t_exit.max(t + 1e-9))
+    }
+}
+
+impl DensityField for VoxelGrid {
+    fn density(&self, p: &Point3) -> f64 {
+        self.lookup(p)
+    }
+
+    fn max_density(&self) -> f64 {
+        self.max_value
+    }
+
+    fn majorant(&self, r: &Ray, t: f64) -> (f64, f64) {
+        VoxelGrid::majorant(self, r, t)
+    }
+}
+
+/// 由体素网格驱动的介质自发光：直接给出强度，或把网格值当作温度按黑体辐射着色
+pub struct VoxelEmission {
+    grid: Arc<VoxelGrid>,
+    color: Color,
+    kelvin_scale: Option<f64>,
+}
+
+impl VoxelEmission {
+    /// Le = color · grid(p)
+    pub fn new(grid: Arc<VoxelGrid>, color: Color) -> Self {
+        Self {
+            grid,
+            color,
+            kelvin_scale: None,
+        }
+    }
+
+    /// 温度 T = kelvin_scale · grid(p)，Le = intensity · blackbody(T) · (T / 1000K)⁴，
+    /// 亮度按 Stefan–Boltzmann 定律随温度四次方增长，颜色取归一化的黑体色
+    pub fn from_temperature(grid: Arc<VoxelGrid>, kelvin_scale: f64, intensity: f64) -> Self {
+        Self {
+            grid,
+            color: Color::new(intensity, intensity, intensity),
+            kelvin_scale: Some(kelvin_scale),
+        }
+    }
+}
+
+impl Texture for VoxelEmission {
+    fn value(&self, _u: f64, _v: f64, p: &Point3) -> Color {
+        let value = self.grid.lookup(p);
+        match self.kelvin_scale {
+            None => value * self.color,
+            Some(scale) => {
+                let t = value * scale;
+                if t <= 0.0 {
+                    return Color::default();
+                }
+                (t / 1000.0).powi(4) * self.color * spectrum::blackbody(t)
+            }
+        }
+    }
+}
+
+fn read_f32s(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+fn read_u32s(bytes: &[u8]) -> Vec<usize> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vxg(header: [u32; 5], blocks: &[u8]) -> Vec<u8> {
+        let mut bytes = b"VXG1".to_vec();
+        for v in header {
+            bytes.extend(v.to_le_bytes());
+        }
+        bytes.extend(blocks);
+        bytes
+    }
+
+    fn load(name: &str, bytes: &[u8]) -> io::Result<VoxelGrid> {
+        let path = std::env::temp_dir().join(name);
+        fs::write(&path, bytes)?;
+        let grid = VoxelGrid::load_sparse(path.to_str().unwrap());
+        fs::remove_file(&path)?;
+        grid
+    }
+
+    #[test]
+    fn loads_sparse_block() {
+        // 2×2×2 网格，一个边长 1 的块放在 (1, 0, 1)
+        let mut block = Vec::new();
+        for v in [1u32, 0, 1] {
+            block.extend(v.to_le_bytes());
+        }
+        block.extend(0.5f32.to_le_bytes());
+        let grid = load("vxg_block.vxg", &vxg([2, 2, 2, 1, 1], &block)).unwrap();
+        assert_eq!(grid.voxel(1, 0, 1), 0.5);
+        assert_eq!(grid.voxel(0, 0, 0), 0.0);
+    }
+
+    #[test]
+    fn rejects_overflowing_header() {
+        let headers = [
+            [2, 2, 2, u32::MAX, 1],
+            [2, 2, 2, 1 << 16, u32::MAX],
+            [u32::MAX, u32::MAX, u32::MAX, 1, 0],
+            [65535, 65535, 65535, 1, 0],
+        ];
+        for (i, header) in headers.into_iter().enumerate() {
+            let err = load(&format!("vxg_overflow_{i}.vxg"), &vxg(header, &[]))
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+        }
+    }
+}